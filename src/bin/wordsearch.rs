@@ -0,0 +1,92 @@
+use std::io::{self, BufRead};
+use std::process;
+use word_search::export::puzzle_with_words_to_string;
+use word_search::generator::{generate_puzzle_seeded, suggest_dimensions};
+
+const USAGE: &str = "Usage: wordsearch [--width N] [--height N] [--seed N] [WORD...]\n\
+Words are read from standard input, one per line, when none are given.";
+
+/// The share of grid cells the words should fill when no size is given.
+const DEFAULT_DENSITY: f32 = 0.5;
+
+#[derive(Debug, PartialEq, Default)]
+struct CliArgs {
+    width: Option<i16>,
+    height: Option<i16>,
+    seed: Option<u64>,
+    words: Vec<String>,
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<CliArgs, String> {
+    let mut parsed = CliArgs::default();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--width" => parsed.width = Some(parse_value(&arg, args.next())?),
+            "--height" => parsed.height = Some(parse_value(&arg, args.next())?),
+            "--seed" => parsed.seed = Some(parse_value(&arg, args.next())?),
+            flag if flag.starts_with("--") => return Err(format!("Unknown option {}", flag)),
+            _ => parsed.words.push(arg),
+        }
+    }
+    Ok(parsed)
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: Option<String>) -> Result<T, String> {
+    let value = value.ok_or_else(|| format!("{} needs a value", flag))?;
+    value.parse::<T>().map_err(|_| format!("Invalid value for {}: {}", flag, value))
+}
+
+fn main() {
+    let mut args = parse_args(std::env::args().skip(1)).unwrap_or_else(|message| {
+        eprintln!("{}\n{}", message, USAGE);
+        process::exit(2);
+    });
+    if args.words.is_empty() {
+        args.words = io::stdin().lock().lines()
+            .map_while(Result::ok)
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+    }
+    if args.words.is_empty() {
+        eprintln!("No words given\n{}", USAGE);
+        process::exit(2);
+    }
+
+    let words = args.words.iter().map(String::as_str).collect::<Vec<&str>>();
+    let (suggested_width, suggested_height) = suggest_dimensions(&words, DEFAULT_DENSITY);
+    let width = args.width.unwrap_or(suggested_width);
+    let height = args.height.unwrap_or(suggested_height);
+    let seed = args.seed.unwrap_or_else(rand::random);
+
+    let (puzzle, not_placed) = generate_puzzle_seeded(width, height, &words, seed);
+    let placed_words = words.iter()
+        .filter(|word| !not_placed.contains(word))
+        .map(|word| word.to_uppercase())
+        .collect::<Vec<String>>();
+    print!("{}", puzzle_with_words_to_string(&puzzle, &placed_words));
+    if !not_placed.is_empty() {
+        println!("\nNot placed:\n{}", not_placed.join("\n"));
+    }
+}
+
+#[cfg(test)]
+mod cli_tests {
+    use super::*;
+
+    fn args(given: &[&str]) -> impl Iterator<Item = String> {
+        given.iter().map(|arg| arg.to_string()).collect::<Vec<String>>().into_iter()
+    }
+
+    #[test]
+    fn it_parses_flags_and_words() {
+        let parsed = parse_args(args(&["--width", "8", "cat", "--seed", "7", "dog"])).unwrap();
+        assert_eq!(parsed, CliArgs { width: Some(8), height: None, seed: Some(7), words: vec!["cat".to_string(), "dog".to_string()] });
+    }
+
+    #[test]
+    fn it_rejects_bad_flags() {
+        assert_eq!(parse_args(args(&["--width"])), Err("--width needs a value".to_string()));
+        assert_eq!(parse_args(args(&["--height", "tall"])), Err("Invalid value for --height: tall".to_string()));
+        assert_eq!(parse_args(args(&["--colour", "red"])), Err("Unknown option --colour".to_string()));
+    }
+}
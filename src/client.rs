@@ -1,42 +1,424 @@
-use crate::generator::generate_puzzle;
+use crate::export::{puzzle_to_string, puzzle_to_svg_with_solution, puzzle_with_words_to_string};
+use crate::presets::{find_preset, PRESETS};
+use crate::json::Puzzle as SavedPuzzle;
+use crate::share::{encode_share_code, ShareParams};
+use crate::generator::{cap_words, dedupe_words, difficulty_score, fill_density, is_sparse, try_start_generation, validate_words, Generation, minimum_dimension, suggest_dimensions, Coordinate, Direction, PlacedWord, PlacementFailure, PuzzleOptions, MAX_DIMENSION};
+use rand::seq::SliceRandom;
+use std::collections::HashSet;
+use std::time::Duration;
 use yew::prelude::*;
+use yew::platform::spawn_local;
+use yew::platform::time::sleep;
+use wasm_bindgen::prelude::wasm_bindgen;
 use wasm_bindgen::{JsCast, JsValue};
-use web_sys::HtmlInputElement;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Blob, BlobPropertyBag, HtmlAnchorElement, HtmlInputElement, Storage, Url};
 use gloo_console::log;
+use gloo_timers::callback::Timeout;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = ["navigator", "clipboard"], js_name = writeText, catch)]
+    fn write_text_to_clipboard(text: &str) -> Result<js_sys::Promise, JsValue>;
+}
+
+async fn copy_to_clipboard(text: &str) -> Result<(), JsValue> {
+    JsFuture::from(write_text_to_clipboard(text)?).await.map(|_| ())
+}
+
+fn download_text(filename: &str, text: &str, mime_type: &str) -> Result<(), JsValue> {
+    let parts = js_sys::Array::of1(&JsValue::from_str(text));
+    let blob = Blob::new_with_str_sequence_and_options(&parts, BlobPropertyBag::new().type_(mime_type))?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+
+    let document = web_sys::window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| JsValue::from_str("No document to download from"))?;
+    let anchor = document.create_element("a")?.unchecked_into::<HtmlAnchorElement>();
+    anchor.set_href(&url);
+    anchor.set_download(filename);
+    anchor.click();
+
+    Url::revoke_object_url(&url)
+}
+
+/// The browser's localStorage, if it is available. Private browsing or disabled storage gives `None`.
+fn local_storage() -> Option<Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+fn load_from_storage(key: &str) -> Option<String> {
+    local_storage()?.get_item(key).ok()?
+}
+
+fn save_to_storage(key: &str, value: &str) {
+    if let Some(storage) = local_storage() {
+        if let Err(err) = storage.set_item(key, value) {
+            log!(err);
+        }
+    }
+}
 
 #[derive(Properties, PartialEq)]
 pub struct PuzzleProps {
     pub puzzle: Vec<Vec<char>>,
+    #[prop_or_default]
+    pub solution: Vec<PlacedWord>,
+    #[prop_or_default]
+    pub highlight: bool,
+    #[prop_or_default]
+    pub selected: HashSet<Coordinate>,
+    #[prop_or_default]
+    pub on_select: Callback<Coordinate>,
+    #[prop_or_default]
+    pub show_labels: bool,
+    #[prop_or_default]
+    pub lowercase: bool,
+    /// Whether to draw a line through each word of the solution.
+    #[prop_or_default]
+    pub show_lines: bool,
+    /// Whether to mark the first letter of each word of the solution as a hint.
+    #[prop_or_default]
+    pub show_start_hints: bool,
+    #[prop_or_default]
+    pub cell_size: CellSize,
+    /// Cells revealed by a hint.
+    #[prop_or_default]
+    pub hinted: HashSet<Coordinate>,
+    /// Whether to shade every other cell like a checkerboard, to make rows easier to follow.
+    #[prop_or_default]
+    pub checkerboard: bool,
 }
 
 #[function_component]
-pub fn Puzzle(PuzzleProps { puzzle }: &PuzzleProps) -> Html {
-    let rows = puzzle.iter().map(|row|
-        html! {
-            <Row row={ row.clone() } />
+pub fn Puzzle(PuzzleProps { puzzle, solution, highlight, selected, on_select, show_labels, lowercase, show_lines, show_start_hints, cell_size, hinted, checkerboard }: &PuzzleProps) -> Html {
+    let highlighted = if *highlight {
+        solution.iter().flat_map(|placed_word| placed_word.coordinates_in(puzzle)).collect::<HashSet<Coordinate>>()
+    } else {
+        HashSet::new()
+    };
+    let start_hints = if *show_start_hints { start_cells(puzzle, solution) } else { HashSet::new() };
+
+    let width = puzzle.first().map(|row| row.len()).unwrap_or(0);
+    let label_columns = if *show_labels { 1 } else { 0 };
+    let style = format!("grid-template-columns: repeat({}, {}rem);", width + label_columns, cell_size.rem());
+    let label_class = classes!(LABEL_CLASS, cell_size.text_class());
+
+    let cells = puzzle.iter().enumerate().map(|(row_index, row)| {
+        let columns = row.iter().enumerate().map(|(column_index, value)| {
+            let coordinate = Coordinate::new(row_index as i16, column_index as i16);
+            html! {
+                <Column
+                    value={ *value }
+                    {coordinate}
+                    highlighted={ highlighted.contains(&coordinate) }
+                    selected={ selected.contains(&coordinate) }
+                    start_hint={ start_hints.contains(&coordinate) }
+                    hinted={ hinted.contains(&coordinate) }
+                    shaded={ *checkerboard && is_shaded(&coordinate) }
+                    on_select={ on_select.clone() }
+                    lowercase={ *lowercase }
+                    cell_size={ *cell_size } />
+            }
         }).collect::<Html>();
 
+        html! {
+            <>
+                if *show_labels {
+                    <span class={ label_class.clone() }>{ row_index + 1 }</span>
+                }
+                { columns }
+            </>
+        }
+    }).collect::<Html>();
+
     html! {
-        <div class="m-4 p-3">
-            { rows }
+        <div class="relative m-4 w-fit">
+            <div class="p-3 grid gap-1" {style}>
+                if *show_labels && width > 0 {
+                    <span class={ label_class.clone() }></span>
+                    { for (0..width).map(|column| html! { <span class={ label_class.clone() }>{ column_label(column) }</span> }) }
+                }
+                { cells }
+            </div>
+            if *show_lines {
+                { solution_overlay(puzzle, solution, *show_labels, cell_size.rem()) }
+            }
         </div>
     }
 }
 
+/// An SVG laid over the grid cells with a rounded line through each word of `solution`.
+fn solution_overlay(puzzle: &[Vec<char>], solution: &[PlacedWord], show_labels: bool, cell_size_rem: f32) -> Html {
+    let pitch = cell_size_rem + CELL_GAP_REM;
+    let width = puzzle.first().map(|row| row.len()).unwrap_or(0) as f32;
+    let height = puzzle.len() as f32;
+    let offset = GRID_PADDING_REM + if show_labels { pitch } else { 0.0 };
+    let overlay_width = width * pitch - CELL_GAP_REM;
+    let overlay_height = height * pitch - CELL_GAP_REM;
+    let style = format!("left: {0}rem; top: {0}rem; width: {1}rem; height: {2}rem;", offset, overlay_width, overlay_height);
+    let view_box = format!("0 0 {} {}", overlay_width, overlay_height);
+
+    html! {
+        <svg class="absolute pointer-events-none" {style} viewBox={view_box} aria-hidden="true">
+            { for solution.iter().filter_map(|placed_word| solution_line_rem(puzzle, placed_word, cell_size_rem)).map(|(x1, y1, x2, y2)| html! {
+                <line x1={x1.to_string()} y1={y1.to_string()} x2={x2.to_string()} y2={y2.to_string()}
+                    stroke="red" stroke-opacity="0.4" stroke-width={(cell_size_rem * 0.8).to_string()} stroke-linecap="round" />
+            }) }
+        </svg>
+    }
+}
+
+/// The endpoints of a line through `placed_word` in rem from the top left of the first cell, as
+/// `(x1, y1, x2, y2)`, for cells `cell_size_rem` wide. Returns `None` for a word that wraps around
+/// an edge, since it can't be drawn as one straight line.
+fn solution_line_rem(puzzle: &[Vec<char>], placed_word: &PlacedWord, cell_size_rem: f32) -> Option<(f32, f32, f32, f32)> {
+    let coordinates = placed_word.coordinates();
+    if coordinates != placed_word.coordinates_in(puzzle) {
+        return None;
+    }
+    let pitch = cell_size_rem + CELL_GAP_REM;
+    let center = |coordinate: &Coordinate| (
+        coordinate.column as f32 * pitch + cell_size_rem / 2.0,
+        coordinate.row as f32 * pitch + cell_size_rem / 2.0,
+    );
+    let (x1, y1) = center(coordinates.first()?);
+    let (x2, y2) = center(coordinates.last()?);
+    Some((x1, y1, x2, y2))
+}
+
+#[cfg(test)]
+mod solution_line_tests {
+    use super::*;
+    use crate::generator::Direction;
+
+    #[test]
+    fn it_runs_from_the_center_of_the_first_letter_to_the_last() {
+        let puzzle = vec![vec![' '; 3]; 3];
+        let across = PlacedWord { word: "CAT".to_string(), start: Coordinate::new(0, 0), direction: Direction::Right };
+        assert_eq!(solution_line_rem(&puzzle, &across, 2.5), Some((1.25, 1.25, 6.75, 1.25)));
+        let diagonal = PlacedWord { word: "CAT".to_string(), start: Coordinate::new(0, 2), direction: Direction::DownLeft };
+        assert_eq!(solution_line_rem(&puzzle, &diagonal, 2.5), Some((6.75, 1.25, 1.25, 6.75)));
+    }
+
+    #[test]
+    fn it_scales_with_the_cell_size() {
+        let puzzle = vec![vec![' '; 3]; 3];
+        let across = PlacedWord { word: "CAT".to_string(), start: Coordinate::new(1, 0), direction: Direction::Right };
+        assert_eq!(solution_line_rem(&puzzle, &across, CellSize::Small.rem()), Some((1.0, 3.25, 5.5, 3.25)));
+    }
+
+    #[test]
+    fn it_skips_words_that_wrap() {
+        let puzzle = vec![vec![' '; 3]];
+        let wrapped = PlacedWord { word: "CAT".to_string(), start: Coordinate::new(0, 2), direction: Direction::Right };
+        assert_eq!(solution_line_rem(&puzzle, &wrapped, 2.5), None);
+    }
+}
+
+/// The cell holding the first letter of each placed word.
+fn start_cells(puzzle: &[Vec<char>], solution: &[PlacedWord]) -> HashSet<Coordinate> {
+    solution.iter()
+        .filter_map(|placed_word| placed_word.coordinates_in(puzzle).first().copied())
+        .collect()
+}
+
+#[cfg(test)]
+mod start_hint_tests {
+    use super::*;
+    use crate::generator::Direction;
+
+    #[test]
+    fn it_marks_only_the_first_letter_of_each_word() {
+        let puzzle = vec![vec![' '; 4]; 4];
+        let solution = vec![
+            PlacedWord { word: "CAT".to_string(), start: Coordinate::new(0, 0), direction: Direction::Right },
+            PlacedWord { word: "DOG".to_string(), start: Coordinate::new(3, 3), direction: Direction::Up },
+        ];
+        assert_eq!(start_cells(&puzzle, &solution), HashSet::from([Coordinate::new(0, 0), Coordinate::new(3, 3)]));
+        assert!(start_cells(&puzzle, &[]).is_empty());
+    }
+}
+
+/// The words of `solution` the player hasn't found yet, meaning not every one of their cells is
+/// selected.
+fn unfound_words(puzzle: &[Vec<char>], solution: &[PlacedWord], selected: &HashSet<Coordinate>) -> Vec<PlacedWord> {
+    solution.iter()
+        .filter(|placed_word| !placed_word.coordinates_in(puzzle).iter().all(|coordinate| selected.contains(coordinate)))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod unfound_word_tests {
+    use super::*;
+    use crate::generator::Direction;
+
+    #[test]
+    fn it_leaves_out_words_whose_cells_are_all_selected() {
+        let puzzle = vec![vec![' '; 3]; 3];
+        let cat = PlacedWord { word: "CAT".to_string(), start: Coordinate::new(0, 0), direction: Direction::Right };
+        let dog = PlacedWord { word: "DOG".to_string(), start: Coordinate::new(0, 0), direction: Direction::Down };
+        let solution = vec![cat.clone(), dog.clone()];
+
+        assert_eq!(unfound_words(&puzzle, &solution, &HashSet::new()), solution);
+        let selected = HashSet::from_iter(cat.coordinates());
+        assert_eq!(unfound_words(&puzzle, &solution, &selected), vec![dog.clone()]);
+        let selected = cat.coordinates().into_iter().chain(dog.coordinates()).collect::<HashSet<Coordinate>>();
+        assert!(unfound_words(&puzzle, &solution, &selected).is_empty());
+    }
+}
+
+/// Whether `coordinate` gets the darker shade of the checkerboard.
+fn is_shaded(coordinate: &Coordinate) -> bool {
+    (coordinate.row + coordinate.column) % 2 == 1
+}
+
+#[cfg(test)]
+mod checkerboard_tests {
+    use super::*;
+
+    #[test]
+    fn it_alternates_along_rows_and_columns() {
+        assert!(!is_shaded(&Coordinate::new(0, 0)));
+        assert!(is_shaded(&Coordinate::new(0, 1)));
+        assert!(is_shaded(&Coordinate::new(1, 0)));
+        assert!(!is_shaded(&Coordinate::new(1, 1)));
+        assert!(!is_shaded(&Coordinate::new(3, 5)));
+    }
+}
+
+/// How big the grid cells and their letters are drawn, so a large grid fits the page or a small
+/// one is easier to read.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum CellSize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+}
+
+impl CellSize {
+    const ALL: [CellSize; 3] = [CellSize::Small, CellSize::Medium, CellSize::Large];
+
+    /// The width and height of each square cell.
+    fn rem(&self) -> f32 {
+        match self {
+            CellSize::Small => 2.0,
+            CellSize::Medium => 2.5,
+            CellSize::Large => 3.25,
+        }
+    }
+
+    fn text_class(&self) -> &'static str {
+        match self {
+            CellSize::Small => "text-base",
+            CellSize::Medium => "text-xl",
+            CellSize::Large => "text-3xl",
+        }
+    }
+
+    fn value(&self) -> &'static str {
+        match self {
+            CellSize::Small => "small",
+            CellSize::Medium => "medium",
+            CellSize::Large => "large",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            CellSize::Small => "Small",
+            CellSize::Medium => "Medium",
+            CellSize::Large => "Large",
+        }
+    }
+
+    fn from_value(value: &str) -> CellSize {
+        CellSize::ALL.into_iter()
+            .find(|size| size.value() == value)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod cell_size_tests {
+    use super::*;
+
+    #[test]
+    fn it_round_trips_the_select_values() {
+        for size in CellSize::ALL {
+            assert_eq!(CellSize::from_value(size.value()), size);
+        }
+        assert_eq!(CellSize::from_value("unknown"), CellSize::Medium);
+    }
+
+    #[test]
+    fn it_grows_from_small_to_large() {
+        assert!(CellSize::Small.rem() < CellSize::Medium.rem());
+        assert!(CellSize::Medium.rem() < CellSize::Large.rem());
+    }
+}
+
+/// The space between cells, from the grid's `gap-1` class.
+const CELL_GAP_REM: f32 = 0.25;
+
+/// The padding around the grid, from its `p-3` class.
+const GRID_PADDING_REM: f32 = 0.75;
+
+const LABEL_CLASS: &str = "flex items-center justify-center font-mono text-gray-400 select-none";
+
+/// Labels columns A to Z, then AA, AB and so on like a spreadsheet.
+fn column_label(index: usize) -> String {
+    let mut label = String::new();
+    let mut remaining = index + 1;
+    while remaining > 0 {
+        remaining -= 1;
+        label.insert(0, (b'A' + (remaining % 26) as u8) as char);
+        remaining /= 26;
+    }
+    label
+}
+
+#[cfg(test)]
+mod label_tests {
+    use super::*;
+
+    #[test]
+    fn it_labels_columns_with_letters() {
+        assert_eq!(column_label(0), "A");
+        assert_eq!(column_label(25), "Z");
+        assert_eq!(column_label(26), "AA");
+        assert_eq!(column_label(27), "AB");
+    }
+}
+
 #[derive(Properties, PartialEq)]
-struct RowProps {
-    row: Vec<char>,
+pub struct PrintablePuzzleProps {
+    pub words: Vec<String>,
+    pub children: Children,
 }
 
-#[function_component]
-fn Row(RowProps { row }: &RowProps) -> Html {
-    let columns = row.iter().map(|column| html! {
-        <Column value={ column.clone() } />
-    }).collect::<Html>();
+/// How many columns the word list is split into.
+const WORD_COLUMNS: usize = 4;
 
+/// Wraps a puzzle and its word list so that each one prints on its own page. The words fill
+/// each column top to bottom so the columns stay balanced.
+#[function_component]
+pub fn PrintablePuzzle(PrintablePuzzleProps { words, children }: &PrintablePuzzleProps) -> Html {
+    let rows = words.len().div_ceil(WORD_COLUMNS);
+    let style = format!("grid-template-rows: repeat({}, auto);", rows.max(1));
     html! {
-        <div class="mx-0 my-2">
-            { columns }
+        <div class="print:break-after-page">
+            if !words.is_empty() {
+                <div>
+                    <h3 class="font-bold underline text-xl">{ "Words:" }</h3>
+                    <div class="grid grid-cols-4 grid-flow-col" {style}>
+                        { for words.iter().map(|word| html! { <span>{ word }</span> }) }
+                    </div>
+                </div>
+            }
+            { children.clone() }
         </div>
     }
 }
@@ -44,13 +426,55 @@ fn Row(RowProps { row }: &RowProps) -> Html {
 #[derive(Properties, PartialEq)]
 struct ColumnProps {
     value: char,
+    coordinate: Coordinate,
+    #[prop_or_default]
+    highlighted: bool,
+    #[prop_or_default]
+    selected: bool,
+    /// Whether this cell starts a word and is shown as a hint.
+    #[prop_or_default]
+    start_hint: bool,
+    #[prop_or_default]
+    hinted: bool,
+    #[prop_or_default]
+    shaded: bool,
+    on_select: Callback<Coordinate>,
+    #[prop_or_default]
+    lowercase: bool,
+    #[prop_or_default]
+    cell_size: CellSize,
 }
 
 #[function_component]
-fn Column(ColumnProps { value }: &ColumnProps) -> Html {
+fn Column(ColumnProps { value, coordinate, highlighted, selected, start_hint, hinted, shaded, on_select, lowercase, cell_size }: &ColumnProps) -> Html {
+    let onclick = {
+        let coordinate = *coordinate;
+        let on_select = on_select.clone();
+        Callback::from(move |_: MouseEvent| on_select.emit(coordinate))
+    };
+    let onkeydown = {
+        let coordinate = *coordinate;
+        let on_select = on_select.clone();
+        Callback::from(move |e: KeyboardEvent| {
+            if e.key() == "Enter" || e.key() == " " {
+                e.prevent_default();
+                on_select.emit(coordinate);
+            }
+        })
+    };
+    let aria_label = format!("Row {} column {}: {}", coordinate.row + 1, column_label(coordinate.column as usize), value);
+
+    let class = classes!(
+        "flex", "items-center", "justify-center", "aspect-square", "font-mono", cell_size.text_class(), "cursor-pointer",
+        highlighted.then_some("bg-yellow-200"),
+        hinted.then_some("bg-orange-200"),
+        (*shaded && !highlighted && !selected && !hinted).then_some("bg-gray-100"),
+        selected.then_some("bg-cyan-300"),
+        start_hint.then_some("font-bold text-rose-600"),
+    );
     html! {
-        <span class="font-mono text-xl my-2 mx-3">
-            { value }
+        <span {class} {onclick} {onkeydown} tabindex="0" role="button" aria-label={aria_label} aria-pressed={selected.to_string()}>
+            { if *lowercase { value.to_lowercase().to_string() } else { value.to_string() } }
         </span>
     }
 }
@@ -60,12 +484,17 @@ fn Column(ColumnProps { value }: &ColumnProps) -> Html {
 struct DimensionProps {
     name: String,
     label: String,
+    value: String,
     on_entry: Callback<i16>,
+    #[prop_or(1)]
+    min: i16,
+    #[prop_or(100)]
+    max: i16,
 }
 
 
 #[function_component]
-fn DimensionInput(DimensionProps { name, label, on_entry }: &DimensionProps) -> Html {
+fn DimensionInput(DimensionProps { name, label, value, on_entry, min, max }: &DimensionProps) -> Html {
     let onchange = {
         let on_entry = on_entry.clone();
         Callback::from(move |e: InputEvent| {
@@ -80,56 +509,607 @@ fn DimensionInput(DimensionProps { name, label, on_entry }: &DimensionProps) ->
         })
     };
 
+    dimension_markup(name, label, value, *min, *max, onchange)
+}
+
+fn dimension_input_id(name: &str) -> String {
+    format!("dimension-{}", name)
+}
+
+fn dimension_markup(name: &str, label: &str, value: &str, min: i16, max: i16, oninput: Callback<InputEvent>) -> Html {
+    let id = dimension_input_id(name);
     html! {
         <div class="grid grid-cols-2">
-            <label class="font-bold" for={name.clone()}>{ label }</label>
-            <input class="border shadow-md" type="number" min="1" max="100" oninput={onchange} />
+            <label class="font-bold" for={id.clone()}>{ label }</label>
+            <input class="border shadow-md" type="number" {id} name={name.to_string()} min={min.to_string()} max={max.to_string()} value={value.to_string()} {oninput} />
         </div>
     }
 }
 
+#[cfg(test)]
+mod dimension_tests {
+    use super::*;
+    use yew::virtual_dom::VNode;
+
+    fn attribute(node: &VNode, key: &str) -> Option<String> {
+        match node {
+            VNode::VTag(tag) => tag.attributes.iter().find(|(name, _)| *name == key).map(|(_, value)| value.to_string()),
+            _ => None,
+        }
+    }
+
+    fn label_for_and_input_id(markup: &Html) -> (String, String) {
+        let VNode::VTag(container) = markup else {
+            panic!("Expected the input to be wrapped in an element");
+        };
+        let children = container.children();
+        (attribute(&children[0], "for").unwrap(), attribute(&children[1], "id").unwrap())
+    }
+
+    #[test]
+    fn it_associates_the_label_with_the_input() {
+        let (label_for, input_id) = label_for_and_input_id(&dimension_markup("width", "Width", "10", 1, 100, Callback::noop()));
+        assert_eq!(label_for, input_id);
+        assert_eq!(input_id, "dimension-width");
+    }
+
+    #[test]
+    fn it_renders_the_min_and_max() {
+        let markup = dimension_markup("width", "Width", "10", 3, 50, Callback::noop());
+        let VNode::VTag(container) = &markup else {
+            panic!("Expected the input to be wrapped in an element");
+        };
+        let input = &container.children()[1];
+        assert_eq!(attribute(input, "min"), Some("3".to_string()));
+        assert_eq!(attribute(input, "max"), Some("50".to_string()));
+    }
+
+    #[test]
+    fn it_gives_each_dimension_a_unique_id() {
+        let (_, width_id) = label_for_and_input_id(&dimension_markup("width", "Width", "10", 1, 100, Callback::noop()));
+        let (_, height_id) = label_for_and_input_id(&dimension_markup("height", "Height", "10", 1, 100, Callback::noop()));
+        assert_ne!(width_id, height_id);
+    }
+}
+
+/// The order the word list is shown in. Generation always uses the order the words were entered.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum WordOrder {
+    Input,
+    Alphabetical,
+    Length,
+}
+
+impl WordOrder {
+    const ALL: [WordOrder; 3] = [WordOrder::Input, WordOrder::Alphabetical, WordOrder::Length];
+
+    fn value(&self) -> &'static str {
+        match self {
+            WordOrder::Input => "input",
+            WordOrder::Alphabetical => "alphabetical",
+            WordOrder::Length => "length",
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            WordOrder::Input => "As entered",
+            WordOrder::Alphabetical => "A to Z",
+            WordOrder::Length => "By length",
+        }
+    }
+
+    fn from_value(value: &str) -> WordOrder {
+        WordOrder::ALL.into_iter()
+            .find(|order| order.value() == value)
+            .unwrap_or(WordOrder::Input)
+    }
+}
+
+/// Returns a copy of `words` in `order`. Words of the same length are sorted alphabetically.
+fn sort_for_display(words: &[String], order: WordOrder) -> Vec<String> {
+    let mut sorted = words.to_vec();
+    match order {
+        WordOrder::Input => {}
+        WordOrder::Alphabetical => sorted.sort(),
+        WordOrder::Length => sorted.sort_by(|a, b| a.chars().count().cmp(&b.chars().count()).then_with(|| a.cmp(b))),
+    }
+    sorted
+}
+
+#[cfg(test)]
+mod word_order_tests {
+    use super::*;
+
+    fn words() -> Vec<String> {
+        ["PEAR", "FIG", "BANANA", "APPLE", "KIWI"].iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn it_keeps_the_input_order() {
+        assert_eq!(sort_for_display(&words(), WordOrder::Input), words());
+    }
+
+    #[test]
+    fn it_sorts_alphabetically() {
+        assert_eq!(sort_for_display(&words(), WordOrder::Alphabetical), vec!["APPLE", "BANANA", "FIG", "KIWI", "PEAR"]);
+    }
+
+    #[test]
+    fn it_sorts_by_length_then_alphabetically() {
+        assert_eq!(sort_for_display(&words(), WordOrder::Length), vec!["FIG", "KIWI", "PEAR", "APPLE", "BANANA"]);
+    }
+
+    #[test]
+    fn it_round_trips_the_select_values() {
+        for order in WordOrder::ALL {
+            assert_eq!(WordOrder::from_value(order.value()), order);
+        }
+        assert_eq!(WordOrder::from_value("unknown"), WordOrder::Input);
+    }
+}
+
+/// Reads a puzzle saved with `Puzzle::to_json`, checking that the grid matches its dimensions
+/// and spells out every placed word.
+fn parse_saved_puzzle(json: &str) -> Result<SavedPuzzle, String> {
+    let saved = SavedPuzzle::from_json(json.trim()).map_err(|err| format!("Could not read the puzzle: {}", err))?;
+    let rows_match = saved.grid.len() == saved.height as usize && saved.grid.iter().all(|row| row.len() == saved.width as usize);
+    if saved.grid.is_empty() || !rows_match {
+        return Err(format!("The puzzle grid is not {}x{}", saved.width, saved.height));
+    }
+    let words_match = saved.placed_words.iter().all(|placed_word| {
+        let letters = placed_word.coordinates_in(&saved.grid).iter()
+            .map(|coordinate| saved.grid[coordinate.row as usize][coordinate.column as usize])
+            .collect::<String>();
+        letters == placed_word.word
+    });
+    if !words_match {
+        return Err("A placed word doesn't match the puzzle grid".to_string());
+    }
+    Ok(saved)
+}
+
+#[cfg(test)]
+mod saved_puzzle_tests {
+    use super::*;
+
+    #[test]
+    fn it_reads_a_saved_puzzle() {
+        let json = r#"{"width":2,"height":2,"grid":[["A","X"],["O","B"]],"placed_words":[{"word":"OX","start":{"row":1,"column":0},"direction":"UpRight"}],"unplaced_words":["ELEPHANT"]}"#;
+        let saved = parse_saved_puzzle(json).unwrap();
+        assert_eq!(saved.grid, vec![vec!['A', 'X'], vec!['O', 'B']]);
+        assert_eq!(saved.placed_words[0].word, "OX");
+        assert_eq!(saved.unplaced_words, vec!["ELEPHANT".to_string()]);
+    }
+
+    #[test]
+    fn it_rejects_broken_or_inconsistent_puzzles() {
+        assert!(parse_saved_puzzle("not json").unwrap_err().starts_with("Could not read the puzzle"));
+        let wrong_size = r#"{"width":3,"height":2,"grid":[["A","X"],["O","B"]],"placed_words":[],"unplaced_words":[]}"#;
+        assert_eq!(parse_saved_puzzle(wrong_size), Err("The puzzle grid is not 3x2".to_string()));
+        let mismatched = r#"{"width":2,"height":2,"grid":[["A","X"],["O","B"]],"placed_words":[{"word":"OX","start":{"row":1,"column":0},"direction":"Right"}],"unplaced_words":[]}"#;
+        assert_eq!(parse_saved_puzzle(mismatched), Err("A placed word doesn't match the puzzle grid".to_string()));
+    }
+}
+
+/// The words in the textarea, one per line, trimmed and without blank lines.
+fn word_lines(text: &str) -> Vec<&str> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod word_lines_tests {
+    use super::*;
+
+    #[test]
+    fn it_trims_lines_and_drops_blank_ones() {
+        assert_eq!(word_lines("cat\n\n  dog  \n   \n\tice cream\n"), vec!["cat", "dog", "ice cream"]);
+        assert!(word_lines(" \n\n").is_empty());
+    }
+}
+
+/// Splits `words` into the uppercased words that were placed and the words listed in `failures`.
+/// Words are compared trimmed and uppercased, since that's how they end up in the puzzle.
+fn partition_placed(words: &[&str], failures: &[PlacementFailure]) -> (Vec<String>, Vec<String>) {
+    let key = |word: &str| word.trim().to_uppercase();
+    let failed_keys = failures.iter().map(|failure| key(&failure.word)).collect::<HashSet<String>>();
+    let placed = words.iter()
+        .map(|word| key(word))
+        .filter(|word| !word.is_empty() && !failed_keys.contains(word))
+        .collect();
+    let not_placed = failures.iter().map(|failure| failure.word.trim().to_string()).collect();
+    (placed, not_placed)
+}
+
+#[cfg(test)]
+mod partition_tests {
+    use super::*;
+    use crate::generator::FailureReason;
+
+    #[test]
+    fn it_ignores_case_and_surrounding_whitespace() {
+        let words = vec!["  Cat", "DOG ", "eLePhAnT", "owl"];
+        let failures = vec![
+            PlacementFailure { word: "ELEPHANT ".to_string(), reason: FailureReason::TooLongForGrid },
+            PlacementFailure { word: "Owl".to_string(), reason: FailureReason::NoSpaceFound },
+        ];
+        let (placed, not_placed) = partition_placed(&words, &failures);
+        assert_eq!(placed, vec!["CAT".to_string(), "DOG".to_string()]);
+        assert_eq!(not_placed, vec!["ELEPHANT".to_string(), "Owl".to_string()]);
+    }
+}
+
+/// The share of grid cells that auto sizing aims to fill with word letters.
+const AUTO_SIZE_DENSITY: f32 = 0.5;
+
+/// Below this share of word letters the grid is mostly filler, so a smaller size is suggested.
+const SPARSE_WARNING_DENSITY: f32 = 0.2;
+
+/// How long the copied confirmation stays on screen, in milliseconds.
+const COPY_MESSAGE_MILLIS: u32 = 2_000;
+
+const DOWNLOAD_FILENAME: &str = "word-search.txt";
+
+const SVG_FILENAME: &str = "word-search.svg";
+
+/// The size of each cell in the downloaded SVG, in pixels.
+const SVG_CELL_SIZE: u32 = 40;
+
+/// The most words used from the textarea, so a huge paste doesn't make generation crawl.
+const MAX_WORDS: usize = 100;
+
+/// How many words are placed before yielding to the browser, so a large grid doesn't freeze the page.
+const WORDS_PER_CHUNK: usize = 5;
+
+/// An extra puzzle from the same words, with the words placed in it spelled as they were entered.
+type Variant = (Vec<Vec<char>>, Vec<String>);
+
+/// The most puzzles generated at once, so a typo in the count doesn't keep the page busy for minutes.
+const MAX_PUZZLES: i16 = 10;
+
+const WORDS_STORAGE_KEY: &str = "word-search.words";
+const WIDTH_STORAGE_KEY: &str = "word-search.width";
+const HEIGHT_STORAGE_KEY: &str = "word-search.height";
+
+/// The form values a puzzle is generated from, along with the seed to generate it with.
+struct GenerateRequest {
+    words: String,
+    width: String,
+    height: String,
+    count: String,
+    seed: u64,
+    direction_preset: Option<DirectionPreset>,
+    /// Whether to save the form for next time. Puzzles opened from a share link aren't saved, so
+    /// they don't replace the visitor's own words.
+    save: bool,
+}
+
+/// Quick choices for the directions words run in, instead of the default of the straight
+/// directions.
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum DirectionPreset {
+    HorizontalOnly,
+    AddDiagonals,
+}
+
+impl DirectionPreset {
+    const ALL: [DirectionPreset; 2] = [DirectionPreset::HorizontalOnly, DirectionPreset::AddDiagonals];
+
+    fn label(&self) -> &'static str {
+        match self {
+            DirectionPreset::HorizontalOnly => "Horizontal only",
+            DirectionPreset::AddDiagonals => "Add diagonals",
+        }
+    }
+
+    fn directions(&self) -> Vec<Direction> {
+        match self {
+            DirectionPreset::HorizontalOnly => vec![Direction::Right],
+            DirectionPreset::AddDiagonals => Direction::ALL.to_vec(),
+        }
+    }
+
+    /// The preset with exactly these directions, in any order. Empty directions, from links made
+    /// before the directions were shared, meant every direction.
+    fn from_directions(directions: &[Direction]) -> Option<DirectionPreset> {
+        if directions.is_empty() {
+            return Some(DirectionPreset::AddDiagonals);
+        }
+        DirectionPreset::ALL.into_iter().find(|preset| {
+            let preset_directions = preset.directions();
+            preset_directions.len() == directions.len() && preset_directions.iter().all(|direction| directions.contains(direction))
+        })
+    }
+}
+
+/// The directions words run in without a preset: across and down, forwards and backwards.
+fn straight_directions() -> Vec<Direction> {
+    Direction::ALL.into_iter().filter(|direction| !direction.is_diagonal()).collect()
+}
+
+/// The options the App generates with. Without a preset words run in the straight directions.
+fn generation_options(direction_preset: Option<DirectionPreset>) -> PuzzleOptions {
+    let directions = direction_preset
+        .map(|preset| preset.directions())
+        .unwrap_or_else(straight_directions);
+    PuzzleOptions { directions, max_words: Some(MAX_WORDS), ..PuzzleOptions::default() }
+}
+
+#[cfg(test)]
+mod direction_preset_tests {
+    use super::*;
+
+    #[test]
+    fn it_generates_with_the_preset_directions() {
+        assert_eq!(generation_options(None).directions, vec![Direction::Up, Direction::Down, Direction::Left, Direction::Right]);
+        assert_eq!(generation_options(Some(DirectionPreset::HorizontalOnly)).directions, vec![Direction::Right]);
+        assert_eq!(generation_options(Some(DirectionPreset::AddDiagonals)).directions, Direction::ALL.to_vec());
+        assert_eq!(generation_options(None).max_words, Some(MAX_WORDS));
+    }
+
+    #[test]
+    fn it_finds_the_preset_in_a_share_code() {
+        for preset in [None, Some(DirectionPreset::HorizontalOnly), Some(DirectionPreset::AddDiagonals)] {
+            let shared = ShareParams { seed: 1, width: 8, height: 8, words: vec!["cat".to_string()], directions: generation_options(preset).directions };
+            let decoded = crate::share::decode_share_code(&encode_share_code(&shared)).unwrap();
+            assert_eq!(DirectionPreset::from_directions(&decoded.directions), preset);
+        }
+        assert_eq!(DirectionPreset::from_directions(&[]), Some(DirectionPreset::AddDiagonals));
+    }
+}
+
 #[function_component]
 pub fn App() -> Html {
     let placed_words: UseStateHandle<Vec<String>> = use_state(|| Vec::new());
     let puzzle_state: UseStateHandle<Vec<Vec<char>>> = use_state(|| vec![]);
+    let solution: UseStateHandle<Vec<PlacedWord>> = use_state(Vec::new);
+    let show_solution: UseStateHandle<bool> = use_state(|| false);
+    let selected_cells: UseStateHandle<HashSet<Coordinate>> = use_state(HashSet::new);
+    let show_labels: UseStateHandle<bool> = use_state(|| false);
+    let show_lines: UseStateHandle<bool> = use_state(|| true);
+    let show_start_hints: UseStateHandle<bool> = use_state(|| false);
+    let checkerboard: UseStateHandle<bool> = use_state(|| false);
+    let lowercase: UseStateHandle<bool> = use_state(|| false);
+    let word_order: UseStateHandle<WordOrder> = use_state(|| WordOrder::Input);
+    let cell_size: UseStateHandle<CellSize> = use_state(CellSize::default);
+    let revealed: UseStateHandle<Option<PlacedWord>> = use_state(|| None);
+    let import_json: UseStateHandle<String> = use_state(String::new);
+    let not_placed: UseStateHandle<Vec<String>> = use_state(Vec::new);
+    let count: UseStateHandle<String> = use_state(|| "1".to_string());
+    let variants: UseStateHandle<Vec<Variant>> = use_state(Vec::new);
     let width: UseStateHandle<String> = use_state(|| "".to_string());
     let height: UseStateHandle<String> = use_state(|| "".to_string());
     let error: UseStateHandle<String> = use_state(|| "".to_string());
+    let duplicates_removed: UseStateHandle<usize> = use_state(|| 0);
+    let words_skipped: UseStateHandle<usize> = use_state(|| 0);
+    let unusual_words: UseStateHandle<Vec<String>> = use_state(Vec::new);
+    let copy_message: UseStateHandle<String> = use_state(|| "".to_string());
+    let generating: UseStateHandle<bool> = use_state(|| false);
+    let progress: UseStateHandle<Option<(usize, usize)>> = use_state(|| None);
+    let last_generated: UseStateHandle<Option<ShareParams>> = use_state(|| None);
 
     let words = use_state(|| String::new());
+    let direction_preset: UseStateHandle<Option<DirectionPreset>> = use_state(|| None);
 
-    let onsubmit = {
-        let words = words.clone();
+    let generate = {
         let puzzle_state = puzzle_state.clone();
         let placed_words = placed_words.clone();
+        let not_placed = not_placed.clone();
+        let variants = variants.clone();
+        let solution = solution.clone();
+        let selected_cells = selected_cells.clone();
+        let error = error.clone();
+        let duplicates_removed = duplicates_removed.clone();
+        let words_skipped = words_skipped.clone();
+        let unusual_words = unusual_words.clone();
+        let generating = generating.clone();
+        let progress = progress.clone();
+        let last_generated = last_generated.clone();
+        Callback::from(move |GenerateRequest { words, width, height, count, seed, direction_preset, save }: GenerateRequest| {
+            if *generating {
+                return;
+            }
+            generating.set(true);
+            selected_cells.set(HashSet::new());
+            if save {
+                save_to_storage(WORDS_STORAGE_KEY, &words);
+                save_to_storage(WIDTH_STORAGE_KEY, &width);
+                save_to_storage(HEIGHT_STORAGE_KEY, &height);
+            }
+
+            let puzzle_state = puzzle_state.clone();
+            let placed_words = placed_words.clone();
+            let not_placed = not_placed.clone();
+            let variants = variants.clone();
+            let solution = solution.clone();
+            let error = error.clone();
+            let duplicates_removed = duplicates_removed.clone();
+            let words_skipped = words_skipped.clone();
+            let unusual_words = unusual_words.clone();
+            let generating = generating.clone();
+            let progress = progress.clone();
+            let last_generated = last_generated.clone();
+            spawn_local(async move {
+                let all_words = word_lines(&words);
+                let unique_words = dedupe_words(&all_words);
+                let split_words = unique_words.iter().map(|word| &word[..]).collect::<Vec<&str>>();
+                duplicates_removed.set(all_words.len() - split_words.len());
+                let (_, _, warnings) = validate_words(i16::MAX, i16::MAX, &split_words, false);
+                unusual_words.set(warnings);
+                let options = generation_options(direction_preset);
+                let (used_words, _) = cap_words(&split_words, options.max_words);
+                let started = match (width.parse::<i16>(), height.parse::<i16>()) {
+                    (Ok(parsed_width), Ok(parsed_height)) => try_start_generation(parsed_width, parsed_height, &split_words, &options, seed)
+                        .map(|(generation, skipped)| (generation, skipped, parsed_width, parsed_height))
+                        .map_err(|generate_error| generate_error.to_string()),
+                    _ => Err("Invalid width or height".to_string()),
+                };
+                match started {
+                    Ok((mut generation, skipped, parsed_width, parsed_height)) => {
+                        // Yield between chunks so the page can redraw the progress instead of freezing.
+                        while !generation.is_done() {
+                            progress.set(Some(generation.progress()));
+                            sleep(Duration::ZERO).await;
+                            generation.place_next(WORDS_PER_CHUNK);
+                        }
+                        let (grid, placed_on_grid, failures, _) = generation.finish();
+                        words_skipped.set(skipped.len());
+                        let (placed, failed) = partition_placed(&used_words, &failures);
+                        placed_words.set(placed);
+                        not_placed.set(failed);
+                        puzzle_state.set(grid);
+                        solution.set(placed_on_grid);
+                        error.set("".to_string());
+                        last_generated.set(Some(ShareParams { seed, width: parsed_width, height: parsed_height, words: unique_words.clone(), directions: options.directions.clone() }));
+
+                        // Variants are seeded like `generate_puzzles`, but placed in chunks as well.
+                        let extra_puzzles = count.parse::<i16>().unwrap_or(1).clamp(1, MAX_PUZZLES) as u64 - 1;
+                        let mut generated_variants = Vec::new();
+                        for index in 0..extra_puzzles {
+                            let mut variant = Generation::new(parsed_width, parsed_height, &used_words, &options, seed.wrapping_add(1 + index));
+                            while !variant.is_done() {
+                                sleep(Duration::ZERO).await;
+                                variant.place_next(WORDS_PER_CHUNK);
+                            }
+                            let (variant_grid, _, variant_failures, _) = variant.finish();
+                            let (variant_words, _) = partition_placed(&used_words, &variant_failures);
+                            generated_variants.push((variant_grid, variant_words));
+                        }
+                        variants.set(generated_variants);
+                    }
+                    Err(message) => {
+                        error.set(message);
+                        puzzle_state.set(Vec::new());
+                        solution.set(Vec::new());
+                        placed_words.set(Vec::new());
+                        not_placed.set(Vec::new());
+                        variants.set(Vec::new());
+                        words_skipped.set(0);
+                        last_generated.set(None);
+                    }
+                }
+                progress.set(None);
+                generating.set(false);
+            });
+        })
+    };
+
+    {
+        let words = words.clone();
         let width = width.clone();
         let height = height.clone();
-        let error = error.clone();
+        let direction_preset = direction_preset.clone();
+        let generate = generate.clone();
+        use_effect_with_deps(move |_| {
+            let search = web_sys::window().and_then(|window| window.location().search().ok());
+            if let Some(shared) = search.as_deref().and_then(ShareParams::from_query_string) {
+                let shared_words = shared.words.join("\n");
+                words.set(shared_words.clone());
+                width.set(shared.width.to_string());
+                height.set(shared.height.to_string());
+                let shared_preset = DirectionPreset::from_directions(&shared.directions);
+                direction_preset.set(shared_preset);
+                generate.emit(GenerateRequest {
+                    words: shared_words,
+                    width: shared.width.to_string(),
+                    height: shared.height.to_string(),
+                    count: "1".to_string(),
+                    seed: shared.seed,
+                    direction_preset: shared_preset,
+                    save: false,
+                });
+            } else {
+                if let Some(saved_words) = load_from_storage(WORDS_STORAGE_KEY) {
+                    words.set(saved_words);
+                }
+                if let Some(saved_width) = load_from_storage(WIDTH_STORAGE_KEY) {
+                    width.set(saved_width);
+                }
+                if let Some(saved_height) = load_from_storage(HEIGHT_STORAGE_KEY) {
+                    height.set(saved_height);
+                }
+            }
+            || ()
+        }, ());
+    }
+
+    let on_direction_preset = {
+        let direction_preset = direction_preset.clone();
+        Callback::from(move |preset: DirectionPreset| {
+            let selected = if *direction_preset == Some(preset) { None } else { Some(preset) };
+            direction_preset.set(selected);
+        })
+    };
+
+    let onsubmit = {
+        let words = words.clone();
+        let width = width.clone();
+        let height = height.clone();
+        let count = count.clone();
+        let direction_preset = direction_preset.clone();
+        let generate = generate.clone();
         Callback::from(move |e: SubmitEvent| {
             e.prevent_default();
+            generate.emit(GenerateRequest {
+                words: (*words).clone(),
+                width: (*width).clone(),
+                height: (*height).clone(),
+                count: (*count).clone(),
+                seed: rand::random(),
+                direction_preset: *direction_preset,
+                save: true,
+            });
+        })
+    };
 
-            let split_words = words.lines().collect::<Vec<&str>>();
-            let parsed_width = width.parse::<i16>();
-            let parsed_height = height.parse::<i16>();
-            if parsed_height.is_err() || parsed_width.is_err() {
-                error.set("Invalid width or height".to_string());
-                puzzle_state.set(Vec::new());
-                placed_words.set(Vec::new());
-            } else {
-                // let split_words_str = split_words.iter().map(|word| &word[..]).collect::<Vec<&str>>();
-                let (puzzle, failed_words) = generate_puzzle(parsed_width.unwrap(), parsed_height.unwrap(), &split_words);
-                puzzle_state.set(puzzle);
-                placed_words.set(split_words.iter()
-                    .filter(|word| !failed_words.contains(&&word[..]))
-                    .map(|word| word.to_uppercase())
-                    .collect());
-
-                if !failed_words.is_empty() {
-                    error.set("Could not place all words".to_string());
-                } else {
-                    error.set("".to_string());
+    let on_shuffle = {
+        let words = words.clone();
+        let width = width.clone();
+        let height = height.clone();
+        let count = count.clone();
+        let direction_preset = direction_preset.clone();
+        let generate = generate.clone();
+        Callback::from(move |_: MouseEvent| {
+            generate.emit(GenerateRequest {
+                words: (*words).clone(),
+                width: (*width).clone(),
+                height: (*height).clone(),
+                count: (*count).clone(),
+                seed: rand::random(),
+                direction_preset: *direction_preset,
+                save: true,
+            });
+        })
+    };
+
+    let on_share = {
+        let last_generated = last_generated.clone();
+        let copy_message = copy_message.clone();
+        Callback::from(move |_: MouseEvent| {
+            // Share what was last generated, not the form, which may have been edited since.
+            let Some(shared) = (*last_generated).clone() else {
+                return;
+            };
+            let Some(location) = web_sys::window().map(|window| window.location()) else {
+                return;
+            };
+            let link = match (location.origin(), location.pathname()) {
+                (Ok(origin), Ok(pathname)) => format!("{}{}?code={}", origin, pathname, encode_share_code(&shared)),
+                _ => return,
+            };
+            let copy_message = copy_message.clone();
+            spawn_local(async move {
+                match copy_to_clipboard(&link).await {
+                    Ok(()) => {
+                        copy_message.set("Copied share link to clipboard".to_string());
+                        Timeout::new(COPY_MESSAGE_MILLIS, move || copy_message.set("".to_string())).forget();
+                    }
+                    Err(err) => log!(err),
                 }
-            }
+            });
         })
     };
 
@@ -142,6 +1122,11 @@ pub fn App() -> Html {
         })
     };
 
+    let on_count_change = {
+        let count = count.clone();
+        Callback::from(move |given: i16| count.set(given.clamp(1, MAX_PUZZLES).to_string()))
+    };
+
     let on_width_change = {
         let width = width.clone();
         let error = error.clone();
@@ -151,6 +1136,227 @@ pub fn App() -> Html {
         })
     };
 
+    let on_auto_size = {
+        let words = words.clone();
+        let width = width.clone();
+        let height = height.clone();
+        let error = error.clone();
+        Callback::from(move |_: MouseEvent| {
+            let all_words = word_lines(&words);
+            let (suggested_width, suggested_height) = suggest_dimensions(&all_words, AUTO_SIZE_DENSITY);
+            error.set("".to_string());
+            width.set(suggested_width.to_string());
+            height.set(suggested_height.to_string());
+        })
+    };
+
+    let on_clear = {
+        let words = words.clone();
+        let width = width.clone();
+        let height = height.clone();
+        let puzzle_state = puzzle_state.clone();
+        let solution = solution.clone();
+        let selected_cells = selected_cells.clone();
+        let placed_words = placed_words.clone();
+        let not_placed = not_placed.clone();
+        let variants = variants.clone();
+        let error = error.clone();
+        let duplicates_removed = duplicates_removed.clone();
+        let words_skipped = words_skipped.clone();
+        let unusual_words = unusual_words.clone();
+        let last_generated = last_generated.clone();
+        Callback::from(move |_: MouseEvent| {
+            selected_cells.set(HashSet::new());
+            words.set(String::new());
+            width.set("".to_string());
+            height.set("".to_string());
+            puzzle_state.set(Vec::new());
+            solution.set(Vec::new());
+            placed_words.set(Vec::new());
+            not_placed.set(Vec::new());
+            variants.set(Vec::new());
+            error.set("".to_string());
+            duplicates_removed.set(0);
+            words_skipped.set(0);
+            unusual_words.set(Vec::new());
+            last_generated.set(None);
+        })
+    };
+
+    let on_copy = {
+        let puzzle_state = puzzle_state.clone();
+        let copy_message = copy_message.clone();
+        Callback::from(move |_: MouseEvent| {
+            let text = puzzle_to_string(&puzzle_state);
+            let copy_message = copy_message.clone();
+            spawn_local(async move {
+                match copy_to_clipboard(&text).await {
+                    Ok(()) => {
+                        copy_message.set("Copied puzzle to clipboard".to_string());
+                        Timeout::new(COPY_MESSAGE_MILLIS, move || copy_message.set("".to_string())).forget();
+                    }
+                    Err(err) => log!(err),
+                }
+            });
+        })
+    };
+
+    let on_download = {
+        let puzzle_state = puzzle_state.clone();
+        let placed_words = placed_words.clone();
+        Callback::from(move |_: MouseEvent| {
+            let text = puzzle_with_words_to_string(&puzzle_state, &placed_words);
+            if let Err(err) = download_text(DOWNLOAD_FILENAME, &text, "text/plain") {
+                log!(err);
+            }
+        })
+    };
+
+    let on_download_svg = {
+        let puzzle_state = puzzle_state.clone();
+        let solution = solution.clone();
+        let show_solution = show_solution.clone();
+        Callback::from(move |_: MouseEvent| {
+            let placed = if *show_solution { (*solution).clone() } else { Vec::new() };
+            let svg = puzzle_to_svg_with_solution(&puzzle_state, &placed, SVG_CELL_SIZE);
+            if let Err(err) = download_text(SVG_FILENAME, &svg, "image/svg+xml") {
+                log!(err);
+            }
+        })
+    };
+
+    let on_select = {
+        let selected_cells = selected_cells.clone();
+        Callback::from(move |coordinate: Coordinate| {
+            let mut cells = (*selected_cells).clone();
+            if !cells.remove(&coordinate) {
+                cells.insert(coordinate);
+            }
+            selected_cells.set(cells);
+        })
+    };
+
+    let unfound = unfound_words(&puzzle_state, &solution, &selected_cells);
+    // A revealed word stops being shown once it's found or a different puzzle is loaded.
+    let hinted = match &*revealed {
+        Some(placed_word) if unfound.contains(placed_word) => placed_word.coordinates_in(&puzzle_state).into_iter().collect(),
+        _ => HashSet::new(),
+    };
+
+    let on_reveal = {
+        let revealed = revealed.clone();
+        let unfound = unfound.clone();
+        Callback::from(move |_: MouseEvent| {
+            revealed.set(unfound.choose(&mut rand::thread_rng()).cloned());
+        })
+    };
+
+    let on_toggle_solution = {
+        let show_solution = show_solution.clone();
+        Callback::from(move |_: MouseEvent| show_solution.set(!*show_solution))
+    };
+
+    let on_toggle_lines = {
+        let show_lines = show_lines.clone();
+        Callback::from(move |_: Event| show_lines.set(!*show_lines))
+    };
+
+    let on_toggle_labels = {
+        let show_labels = show_labels.clone();
+        Callback::from(move |_: Event| show_labels.set(!*show_labels))
+    };
+
+    let on_toggle_start_hints = {
+        let show_start_hints = show_start_hints.clone();
+        Callback::from(move |_: Event| show_start_hints.set(!*show_start_hints))
+    };
+
+    let on_toggle_checkerboard = {
+        let checkerboard = checkerboard.clone();
+        Callback::from(move |_: Event| checkerboard.set(!*checkerboard))
+    };
+
+    let on_toggle_lowercase = {
+        let lowercase = lowercase.clone();
+        Callback::from(move |_: Event| lowercase.set(!*lowercase))
+    };
+
+    let display_case = {
+        let lowercase = *lowercase;
+        move |word: &String| if lowercase { word.to_lowercase() } else { word.clone() }
+    };
+
+    let on_word_order_change = {
+        let word_order = word_order.clone();
+        Callback::from(move |e: Event| {
+            let target = e.target().expect("Event should have a target when dispatched");
+            word_order.set(WordOrder::from_value(&target.unchecked_into::<HtmlInputElement>().value()));
+        })
+    };
+
+    let on_cell_size_change = {
+        let cell_size = cell_size.clone();
+        Callback::from(move |e: Event| {
+            let target = e.target().expect("Event should have a target when dispatched");
+            cell_size.set(CellSize::from_value(&target.unchecked_into::<HtmlInputElement>().value()));
+        })
+    };
+
+    let on_preset_change = {
+        let words = words.clone();
+        let error = error.clone();
+        Callback::from(move |e: Event| {
+            let target = e.target().expect("Event should have a target when dispatched");
+            let input = target.unchecked_into::<HtmlInputElement>();
+
+            if let Some(preset) = find_preset(&input.value()) {
+                words.set(preset.words.join("\n"));
+                error.set("".to_string());
+            }
+        })
+    };
+
+    let on_import_change = {
+        let import_json = import_json.clone();
+        Callback::from(move |e: Event| {
+            let target = e.target().expect("Event should have a target when dispatched");
+            import_json.set(target.unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+
+    let on_import = {
+        let import_json = import_json.clone();
+        let puzzle_state = puzzle_state.clone();
+        let solution = solution.clone();
+        let selected_cells = selected_cells.clone();
+        let placed_words = placed_words.clone();
+        let not_placed = not_placed.clone();
+        let variants = variants.clone();
+        let error = error.clone();
+        let duplicates_removed = duplicates_removed.clone();
+        let words_skipped = words_skipped.clone();
+        let unusual_words = unusual_words.clone();
+        let last_generated = last_generated.clone();
+        Callback::from(move |_: MouseEvent| {
+            match parse_saved_puzzle(&import_json) {
+                Ok(saved) => {
+                    selected_cells.set(HashSet::new());
+                    placed_words.set(saved.placed_words.iter().map(|placed_word| placed_word.word.clone()).collect());
+                    not_placed.set(saved.unplaced_words);
+                    puzzle_state.set(saved.grid);
+                    solution.set(saved.placed_words);
+                    variants.set(Vec::new());
+                    duplicates_removed.set(0);
+                    words_skipped.set(0);
+                    unusual_words.set(Vec::new());
+                    last_generated.set(None);
+                    error.set("".to_string());
+                }
+                Err(message) => error.set(message),
+            }
+        })
+    };
+
     let on_words_change = {
         let words = words.clone();
         let error = error.clone();
@@ -163,23 +1369,85 @@ pub fn App() -> Html {
         })
     };
 
+    let density = fill_density(&puzzle_state, &solution);
+    let sparse_hint = is_sparse(&puzzle_state, &solution, SPARSE_WARNING_DENSITY).then(|| {
+        let (suggested_width, suggested_height) = suggest_dimensions(&word_lines(&words), AUTO_SIZE_DENSITY);
+        format!("Only {:.0}% of the grid is word letters. Try a smaller grid, such as {}x{}.", density * 100.0, suggested_width, suggested_height)
+    });
+    let minimum_size = minimum_dimension(&word_lines(&words)).clamp(1, MAX_DIMENSION);
+
     html! {
         <div class="container mx-auto">
             <div>
                 <h1 class="my-5 font-bold text-3xl underline print:hidden">{ "Word Search puzzle Generator" }</h1>
             </div>
             if !(*error).is_empty() {
-                <div class="md:w-1/5 bg-red-200 border border-red-400 text-red-700 m-3 px-3 py-3 rounded" role="alert">
+                <div class="md:w-1/5 bg-red-200 border border-red-400 text-red-700 m-3 px-3 py-3 rounded" role="alert" aria-live="assertive">
                     <strong class="font-bold">{ "Error: " }</strong>
                     <span class="block sm:inline">{ (*error).clone() }</span>
                 </div>
             }
-            <form class="md:w-1/5 space-y-3 p-2 print:hidden" name="word_form" {onsubmit}>
+            if !(*not_placed).is_empty() {
+                <div class="md:w-1/5 bg-red-200 border border-red-400 text-red-700 m-3 px-3 py-3 rounded" role="alert" aria-live="assertive">
+                    <strong class="font-bold">{ "Error: " }</strong>
+                    <span class="block sm:inline">{ "Could not place all words" }</span>
+                    <h4 class="font-bold mt-2">{ "Not placed:" }</h4>
+                    <ul class="list-disc ml-5">
+                        { for (*not_placed).iter().map(|word| html! { <li>{ word }</li> }) }
+                    </ul>
+                </div>
+            }
+            if *words_skipped > 0 {
+                <div class="md:w-1/5 m-3 text-gray-600 print:hidden" role="status">
+                    { format!("Only the first {} words were used", MAX_WORDS) }
+                </div>
+            }
+            if let Some(hint) = sparse_hint {
+                <div class="md:w-1/5 bg-yellow-100 border border-yellow-400 text-yellow-800 m-3 px-3 py-3 rounded print:hidden" role="status">
+                    { hint }
+                </div>
+            }
+            if *duplicates_removed > 0 {
+                <div class="md:w-1/5 m-3 text-gray-600 print:hidden">
+                    { format!("Removed {} duplicate word(s)", *duplicates_removed) }
+                </div>
+            }
+            if !(*unusual_words).is_empty() {
+                <div class="md:w-1/5 bg-yellow-100 border border-yellow-400 text-yellow-800 m-3 px-3 py-3 rounded print:hidden" role="status">
+                    { format!("These words have characters other than letters and may stand out: {}", (*unusual_words).join(", ")) }
+                </div>
+            }
+            <form class="md:w-1/5 space-y-3 p-2 print:hidden" name="word_form" aria-label="Puzzle settings" {onsubmit}>
                 <div>
-                    <DimensionInput name="width" label="Width" on_entry={on_width_change} />
+                    <DimensionInput name="width" label="Width" value={(*width).clone()} on_entry={on_width_change} min={minimum_size} max={MAX_DIMENSION} />
                 </div>
                 <div>
-                    <DimensionInput name="height" label="Height" on_entry={on_height_change} />
+                    <DimensionInput name="height" label="Height" value={(*height).clone()} on_entry={on_height_change} min={minimum_size} max={MAX_DIMENSION} />
+                </div>
+                <div>
+                    <DimensionInput name="count" label="Puzzles" value={(*count).clone()} on_entry={on_count_change} max={MAX_PUZZLES} />
+                </div>
+                <div>
+                    <div class="grid grid-cols-2">
+                        <label class="font-bold" for="preset">{ "Preset: " }</label>
+                        <select class="border shadow-md" id="preset" name="preset" onchange={on_preset_change}>
+                            <option value="" selected=true>{ "Choose a word list" }</option>
+                            { for PRESETS.iter().map(|preset| html! { <option value={preset.name}>{ preset.name }</option> }) }
+                        </select>
+                    </div>
+                </div>
+                <div class="space-x-2" role="group" aria-label="Word directions">
+                    { for DirectionPreset::ALL.into_iter().map(|preset| {
+                        let pressed = *direction_preset == Some(preset);
+                        let class = classes!(
+                            "rounded-full", "p-2", "font-bold", "shadow-md",
+                            if pressed { "bg-cyan-300" } else { "bg-gray-200 hover:bg-gray-300" },
+                        );
+                        let on_direction_preset = on_direction_preset.clone();
+                        html! {
+                            <button {class} type="button" aria-pressed={pressed.to_string()} onclick={move |_: MouseEvent| on_direction_preset.emit(preset)}>{ preset.label() }</button>
+                        }
+                    }) }
                 </div>
                 <div>
                     <div class="grid grid-cols-2">
@@ -187,19 +1455,96 @@ pub fn App() -> Html {
                         <textarea class="border shadow-md" id="words" name="words" rows="10" cols="50" value={(*words).clone()} onchange={on_words_change} />
                     </div>
                 </div>
-                <div class="py-5">
-                    <button class="rounded-full p-2 font-bold bg-cyan-200 hover:bg-cyan-300 shadow-md">{ "Generate" }</button>
+                <div class="py-5 space-x-2">
+                    <button class="rounded-full p-2 font-bold bg-cyan-200 hover:bg-cyan-300 shadow-md disabled:opacity-50 disabled:cursor-wait" type="submit" disabled={*generating} aria-busy={generating.to_string()}>
+                        { match (*generating, *progress) {
+                            (true, Some((done, total))) => format!("Generating {}/{}...", done, total),
+                            (true, None) => "Generating...".to_string(),
+                            (false, _) => "Generate".to_string(),
+                        } }
+                    </button>
+                    <button class="rounded-full p-2 font-bold bg-gray-200 hover:bg-gray-300 shadow-md" type="button" onclick={on_auto_size} aria-label="Pick a size that fits the words">{ "Auto size" }</button>
+                    <button class="rounded-full p-2 font-bold bg-gray-200 hover:bg-gray-300 shadow-md disabled:opacity-50" type="button" onclick={on_shuffle} disabled={(*puzzle_state).is_empty() || *generating} aria-label="Rearrange the same words">{ "Shuffle" }</button>
+                    <button class="rounded-full p-2 font-bold bg-gray-200 hover:bg-gray-300 shadow-md" type="button" onclick={on_clear} aria-label="Clear the words and puzzle">{ "Clear" }</button>
                 </div>
             </form>
-            if !(*placed_words).is_empty() {
-                <div>
-                    <h3 class="font-bold underline text-xl">{ "Words:" }</h3>
-                    <div class="grid grid-cols-4">
-                        { for (*placed_words).iter().map(|word| html! { <span>{ word }</span> }) }
-                    </div>
+            <details class="md:w-1/5 m-2 print:hidden">
+                <summary class="font-bold cursor-pointer">{ "Load a saved puzzle" }</summary>
+                <label class="block" for="import-json">{ "Puzzle JSON:" }</label>
+                <textarea class="border shadow-md w-full" id="import-json" rows="5" value={(*import_json).clone()} onchange={on_import_change} />
+                <button class="rounded-full p-2 font-bold bg-gray-200 hover:bg-gray-300 shadow-md" type="button" onclick={on_import} disabled={(*import_json).trim().is_empty()}>{ "Load" }</button>
+            </details>
+            if !(*puzzle_state).is_empty() {
+                <div class="m-4 text-gray-600 print:hidden">
+                    { format!(
+                        "{} words placed, {} not placed, {}x{} grid, {:.0}% word letters / {:.0}% filler, difficulty {}/100",
+                        (*solution).len(),
+                        (*not_placed).len(),
+                        (*puzzle_state)[0].len(),
+                        (*puzzle_state).len(),
+                        density * 100.0,
+                        (1.0 - density) * 100.0,
+                        difficulty_score(&puzzle_state, &solution),
+                    ) }
+                </div>
+                <div class="m-4 space-x-2 print:hidden">
+                    <button class="rounded-full p-2 font-bold bg-gray-200 hover:bg-gray-300 shadow-md" type="button" onclick={on_copy}>{ "Copy puzzle" }</button>
+                    <button class="rounded-full p-2 font-bold bg-gray-200 hover:bg-gray-300 shadow-md" type="button" onclick={on_download}>{ "Download" }</button>
+                    <button class="rounded-full p-2 font-bold bg-gray-200 hover:bg-gray-300 shadow-md" type="button" onclick={on_download_svg}>{ "Download SVG" }</button>
+                    <button class="rounded-full p-2 font-bold bg-gray-200 hover:bg-gray-300 shadow-md" type="button" onclick={on_share}>{ "Copy share link" }</button>
+                    <button class="rounded-full p-2 font-bold bg-gray-200 hover:bg-gray-300 shadow-md disabled:opacity-50" type="button" onclick={on_reveal} disabled={unfound.is_empty()}>{ "Reveal a word" }</button>
+                    <button class="rounded-full p-2 font-bold bg-gray-200 hover:bg-gray-300 shadow-md" type="button" onclick={on_toggle_solution}>
+                        { if *show_solution { "Hide solution" } else { "Show solution" } }
+                    </button>
+                    if *show_solution {
+                        <label>
+                            <input type="checkbox" checked={*show_lines} onchange={on_toggle_lines} />
+                            { " Solution lines" }
+                        </label>
+                    }
+                    <label>
+                        <input type="checkbox" checked={*show_labels} onchange={on_toggle_labels} />
+                        { " Show labels" }
+                    </label>
+                    <label>
+                        <input type="checkbox" checked={*show_start_hints} onchange={on_toggle_start_hints} />
+                        { " First letter hints" }
+                    </label>
+                    <label>
+                        <input type="checkbox" checked={*checkerboard} onchange={on_toggle_checkerboard} />
+                        { " Shade alternate cells" }
+                    </label>
+                    <label>
+                        <input type="checkbox" checked={*lowercase} onchange={on_toggle_lowercase} />
+                        { " Lowercase" }
+                    </label>
+                    <label>
+                        { "Sort words: " }
+                        <select class="border shadow-md" onchange={on_word_order_change}>
+                            { for WordOrder::ALL.iter().map(|order| html! {
+                                <option value={order.value()} selected={*order == *word_order}>{ order.label() }</option>
+                            }) }
+                        </select>
+                    </label>
+                    <label>
+                        { "Cell size: " }
+                        <select class="border shadow-md" onchange={on_cell_size_change}>
+                            { for CellSize::ALL.iter().map(|size| html! {
+                                <option value={size.value()} selected={*size == *cell_size}>{ size.label() }</option>
+                            }) }
+                        </select>
+                    </label>
+                    <span class="text-green-700">{ (*copy_message).clone() }</span>
                 </div>
             }
-            <Puzzle puzzle={(*puzzle_state).clone()} />
+            <PrintablePuzzle words={sort_for_display(&placed_words, *word_order).iter().map(display_case).collect::<Vec<String>>()}>
+                <Puzzle puzzle={(*puzzle_state).clone()} solution={(*solution).clone()} highlight={*show_solution} selected={(*selected_cells).clone()} {on_select} show_labels={*show_labels} lowercase={*lowercase} show_lines={*show_solution && *show_lines} show_start_hints={*show_start_hints} cell_size={*cell_size} {hinted} checkerboard={*checkerboard} />
+            </PrintablePuzzle>
+            { for (*variants).iter().map(|(variant, variant_words)| html! {
+                <PrintablePuzzle words={sort_for_display(variant_words, *word_order).iter().map(display_case).collect::<Vec<String>>()}>
+                    <Puzzle puzzle={variant.clone()} show_labels={*show_labels} lowercase={*lowercase} cell_size={*cell_size} checkerboard={*checkerboard} />
+                </PrintablePuzzle>
+            }) }
         </div>
     }
 }
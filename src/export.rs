@@ -0,0 +1,196 @@
+use crate::generator::PlacedWord;
+
+/// Renders the puzzle as plain text, with the letters of each row separated by a space and
+/// each row on its own line.
+pub fn puzzle_to_string(puzzle: &[Vec<char>]) -> String {
+    puzzle_to_string_with_separator(puzzle, " ")
+}
+
+/// Renders the puzzle as plain text, with the letters of each row separated by `sep`.
+pub fn puzzle_to_string_with_separator(puzzle: &[Vec<char>], sep: &str) -> String {
+    puzzle.iter()
+        .map(|row| row.iter().map(|cell| cell.to_string()).collect::<Vec<String>>().join(sep))
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Renders the puzzle as plain text followed by the list of words to find.
+pub fn puzzle_with_words_to_string(puzzle: &[Vec<char>], words: &[String]) -> String {
+    format!("{}\n\nWords:\n{}\n", puzzle_to_string(puzzle), words.join("\n"))
+}
+
+/// Builds an answer key: a copy of the puzzle where every cell that is not part of a placed word
+/// is blanked out with a space.
+pub fn solution_grid(puzzle: &[Vec<char>], placed: &[PlacedWord]) -> Vec<Vec<char>> {
+    solution_grid_with_blank(puzzle, placed, ' ')
+}
+
+/// Like `solution_grid`, but blanks out filler cells with `blank`.
+pub fn solution_grid_with_blank(puzzle: &[Vec<char>], placed: &[PlacedWord], blank: char) -> Vec<Vec<char>> {
+    let mut solution = puzzle.iter()
+        .map(|row| vec![blank; row.len()])
+        .collect::<Vec<Vec<char>>>();
+
+    for coordinate in placed.iter().flat_map(|placed_word| placed_word.coordinates_in(puzzle)) {
+        let (row, column) = (coordinate.row as usize, coordinate.column as usize);
+        solution[row][column] = puzzle[row][column];
+    }
+    solution
+}
+
+/// Renders the puzzle as an SVG image with a square cell of `cell_size` pixels for each letter.
+pub fn puzzle_to_svg(puzzle: &[Vec<char>], cell_size: u32) -> String {
+    puzzle_to_svg_with_solution(puzzle, &[], cell_size)
+}
+
+/// Like `puzzle_to_svg`, but also draws a line through each of the `placed` words, from the
+/// center of its first letter to the center of its last. Words that wrap around an edge are
+/// left out, since they can't be drawn as one straight line.
+pub fn puzzle_to_svg_with_solution(puzzle: &[Vec<char>], placed: &[PlacedWord], cell_size: u32) -> String {
+    let width = puzzle.first().map(|row| row.len()).unwrap_or(0) as u32 * cell_size;
+    let height = puzzle.len() as u32 * cell_size;
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\" font-family=\"monospace\" font-size=\"{2}\">\n",
+        width, height, cell_size * 3 / 5,
+    );
+    svg.push_str(&format!("<rect width=\"{}\" height=\"{}\" fill=\"white\" stroke=\"black\"/>\n", width, height));
+
+    for (row, cells) in puzzle.iter().enumerate() {
+        for (column, cell) in cells.iter().enumerate() {
+            if cell.is_whitespace() || cell.is_control() {
+                continue;
+            }
+            let (x, y) = cell_center(row as i16, column as i16, cell_size);
+            svg.push_str(&format!(
+                "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\">{}</text>\n",
+                x, y, escape_xml(*cell),
+            ));
+        }
+    }
+
+    for placed_word in placed {
+        if let Some((x1, y1, x2, y2)) = solution_line(puzzle, placed_word, cell_size) {
+            svg.push_str(&format!(
+                "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"red\" stroke-opacity=\"0.5\" stroke-width=\"{}\" stroke-linecap=\"round\"/>\n",
+                x1, y1, x2, y2, cell_size * 3 / 5,
+            ));
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// The endpoints of a line through `placed_word` on a grid of `cell_size` pixel cells, as
+/// `(x1, y1, x2, y2)`. Returns `None` for a word that wraps around an edge of `puzzle`.
+pub fn solution_line(puzzle: &[Vec<char>], placed_word: &PlacedWord, cell_size: u32) -> Option<(u32, u32, u32, u32)> {
+    let coordinates = placed_word.coordinates();
+    if coordinates != placed_word.coordinates_in(puzzle) {
+        return None;
+    }
+    let (first, last) = (coordinates.first()?, coordinates.last()?);
+    let (x1, y1) = cell_center(first.row, first.column, cell_size);
+    let (x2, y2) = cell_center(last.row, last.column, cell_size);
+    Some((x1, y1, x2, y2))
+}
+
+fn cell_center(row: i16, column: i16, cell_size: u32) -> (u32, u32) {
+    (column as u32 * cell_size + cell_size / 2, row as u32 * cell_size + cell_size / 2)
+}
+
+fn escape_xml(cell: char) -> String {
+    match cell {
+        '&' => "&amp;".to_string(),
+        '<' => "&lt;".to_string(),
+        '>' => "&gt;".to_string(),
+        '"' => "&quot;".to_string(),
+        '\'' => "&apos;".to_string(),
+        _ => cell.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod export_tests {
+    use super::*;
+    use crate::generator::{Coordinate, Direction};
+
+    #[test]
+    fn it_converts_a_puzzle_to_a_string() {
+        let puzzle = vec![vec!['A', 'B'], vec!['C', 'D']];
+        assert_eq!(puzzle_to_string(&puzzle), "A B\nC D");
+    }
+
+    #[test]
+    fn it_converts_a_puzzle_to_a_string_with_a_separator() {
+        let puzzle = vec![vec!['A', 'B'], vec!['C', 'D']];
+        assert_eq!(puzzle_to_string_with_separator(&puzzle, ","), "A,B\nC,D");
+        assert_eq!(puzzle_to_string_with_separator(&puzzle, "\t"), "A\tB\nC\tD");
+    }
+
+    #[test]
+    fn it_converts_a_puzzle_and_its_words_to_a_string() {
+        let puzzle = vec![vec!['C', 'A', 'T'], vec!['O', 'X', 'Y']];
+        let words = vec!["CAT".to_string(), "OX".to_string()];
+        assert_eq!(puzzle_with_words_to_string(&puzzle, &words), "C A T\nO X Y\n\nWords:\nCAT\nOX\n");
+    }
+
+    #[test]
+    fn it_blanks_out_filler_in_the_solution() {
+        let puzzle = vec![
+            vec!['C', 'A', 'T'],
+            vec!['X', 'N', 'Y'],
+            vec!['Z', 'T', 'Q'],
+        ];
+        let placed = vec![
+            PlacedWord { word: "CAT".to_string(), start: Coordinate::new(0, 0), direction: Direction::Right },
+            PlacedWord { word: "ANT".to_string(), start: Coordinate::new(0, 1), direction: Direction::Down },
+        ];
+
+        assert_eq!(solution_grid(&puzzle, &placed), vec![
+            vec!['C', 'A', 'T'],
+            vec![' ', 'N', ' '],
+            vec![' ', 'T', ' '],
+        ]);
+        assert_eq!(solution_grid_with_blank(&puzzle, &placed, '.'), vec![
+            vec!['C', 'A', 'T'],
+            vec!['.', 'N', '.'],
+            vec!['.', 'T', '.'],
+        ]);
+    }
+
+    #[test]
+    fn it_renders_a_text_element_for_each_letter() {
+        let puzzle = vec![vec!['A', 'B', 'C'], vec!['D', ' ', '<']];
+        let svg = puzzle_to_svg(&puzzle, 40);
+        assert!(svg.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"120\" height=\"80\""));
+        assert_eq!(svg.matches("<text").count(), 5);
+        assert!(svg.contains(">&lt;</text>"));
+        assert!(svg.contains("<text x=\"60\" y=\"20\" text-anchor=\"middle\" dominant-baseline=\"central\">B</text>"));
+        assert!(!svg.contains("<line"));
+        assert!(svg.trim_end().ends_with("</svg>"));
+    }
+
+    #[test]
+    fn it_draws_a_line_through_each_placed_word() {
+        let puzzle = vec![
+            vec!['C', 'A', 'T'],
+            vec!['X', 'N', 'Y'],
+            vec!['Z', 'T', 'Q'],
+        ];
+        let placed = vec![
+            PlacedWord { word: "CAT".to_string(), start: Coordinate::new(0, 0), direction: Direction::Right },
+            PlacedWord { word: "TNZ".to_string(), start: Coordinate::new(0, 2), direction: Direction::DownLeft },
+        ];
+        let svg = puzzle_to_svg_with_solution(&puzzle, &placed, 10);
+        assert_eq!(svg.matches("<line").count(), 2);
+        assert_eq!(solution_line(&puzzle, &placed[0], 10), Some((5, 5, 25, 5)));
+        assert_eq!(solution_line(&puzzle, &placed[1], 10), Some((25, 5, 5, 25)));
+    }
+
+    #[test]
+    fn it_leaves_out_lines_for_wrapped_words() {
+        let puzzle = vec![vec!['T', 'C', 'A']];
+        let wrapped = PlacedWord { word: "CAT".to_string(), start: Coordinate::new(0, 1), direction: Direction::Right };
+        assert_eq!(solution_line(&puzzle, &wrapped, 10), None);
+    }
+}
@@ -1,210 +1,3230 @@
 use rand::prelude::*;
+use rand::distributions::WeightedIndex;
+use rand::rngs::StdRng;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
 
 pub fn generate_puzzle<'a>(width: i16, height: i16, words: &'a Vec<&'a str>)  -> (Vec<Vec<char>>, Vec<&'a str>) {
+    generate_puzzle_seeded(width, height, words, thread_rng().gen())
+}
+
+/// Generates a puzzle using an RNG seeded from `seed`, so the same seed, dimensions and words
+/// always produce the same grid.
+pub fn generate_puzzle_seeded<'a>(width: i16, height: i16, words: &'a Vec<&'a str>, seed: u64) -> (Vec<Vec<char>>, Vec<&'a str>) {
+    let (puzzle, _, words_not_placed) = generate_puzzle_with_solution_seeded(width, height, words, seed);
+    (puzzle, words_not_placed)
+}
+
+/// Like `generate_puzzle`, but also returns where each word was placed.
+pub fn generate_puzzle_with_solution<'a>(width: i16, height: i16, words: &'a Vec<&'a str>) -> (Vec<Vec<char>>, Vec<PlacedWord>, Vec<&'a str>) {
+    generate_puzzle_with_solution_seeded(width, height, words, thread_rng().gen())
+}
+
+pub fn generate_puzzle_with_solution_seeded<'a>(width: i16, height: i16, words: &'a Vec<&'a str>, seed: u64) -> (Vec<Vec<char>>, Vec<PlacedWord>, Vec<&'a str>) {
+    let (puzzle, placed_words, failures) = generate_puzzle_with_options(width, height, words, &PuzzleOptions::default(), seed);
+    (puzzle, placed_words, failed_words(words, &failures))
+}
+
+/// Generates a puzzle where words are only placed in one of the `allowed` directions.
+/// An empty `allowed` list means every direction may be used.
+pub fn generate_puzzle_with_directions<'a>(width: i16, height: i16, words: &'a Vec<&'a str>, allowed: &[Direction]) -> (Vec<Vec<char>>, Vec<&'a str>) {
+    let options = PuzzleOptions { directions: allowed.to_vec(), ..PuzzleOptions::default() };
+    let (puzzle, _, failures) = generate_puzzle_with_options(width, height, words, &options, thread_rng().gen());
+    (puzzle, failed_words(words, &failures))
+}
+
+/// Generates a puzzle whose word directions and overlaps are determined by `difficulty`.
+pub fn generate_puzzle_with_difficulty<'a>(width: i16, height: i16, words: &'a Vec<&'a str>, difficulty: Difficulty) -> (Vec<Vec<char>>, Vec<&'a str>) {
+    let (puzzle, _, failures) = generate_puzzle_with_options(width, height, words, &difficulty.options(), thread_rng().gen());
+    (puzzle, failed_words(words, &failures))
+}
+
+pub fn generate_puzzle_with_options(width: i16, height: i16, words: &[&str], options: &PuzzleOptions, seed: u64) -> (Vec<Vec<char>>, Vec<PlacedWord>, Vec<PlacementFailure>) {
+    let (puzzle, placed_words, failures, _) = generate_puzzle_with_stats(width, height, words, options, seed);
+    (puzzle, placed_words, failures)
+}
+
+/// How much work generating a puzzle took.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct GenerationStats {
+    /// How many positions were checked to see if a word fits there.
+    pub attempts: usize,
+    pub placed: usize,
+    pub failed: usize,
+}
+
+/// Like `generate_puzzle_with_options`, but also reports how much work placing the words took.
+pub fn generate_puzzle_with_stats(width: i16, height: i16, words: &[&str], options: &PuzzleOptions, seed: u64) -> (Vec<Vec<char>>, Vec<PlacedWord>, Vec<PlacementFailure>, GenerationStats) {
+    generate_reporting_progress(width, height, words, options, seed, &mut |_, _| {})
+}
+
+/// Like `generate_puzzle_with_options`, but calls `on_progress` with the number of words tried so
+/// far and the total after each word, for example to drive a progress bar.
+pub fn generate_puzzle_with_progress(width: i16, height: i16, words: &[&str], options: &PuzzleOptions, seed: u64, mut on_progress: impl FnMut(usize, usize)) -> (Vec<Vec<char>>, Vec<PlacedWord>, Vec<PlacementFailure>) {
+    let (puzzle, placed_words, failures, _) = generate_reporting_progress(width, height, words, options, seed, &mut on_progress);
+    (puzzle, placed_words, failures)
+}
+
+fn generate_reporting_progress(width: i16, height: i16, words: &[&str], options: &PuzzleOptions, seed: u64, on_progress: &mut dyn FnMut(usize, usize)) -> (Vec<Vec<char>>, Vec<PlacedWord>, Vec<PlacementFailure>, GenerationStats) {
+    let mut generation = Generation::new(width, height, words, options, seed);
+    while !generation.is_done() {
+        generation.place_next(1);
+        let (done, total) = generation.progress();
+        on_progress(done, total);
+    }
+    generation.finish()
+}
+
+/// A puzzle generated a few words at a time, so the caller can do other work in between, such as
+/// letting the browser redraw while a large grid is filled. For the same arguments it produces
+/// the same puzzle as `generate_puzzle_with_stats`.
+pub struct Generation {
+    width: i16,
+    height: i16,
+    options: PuzzleOptions,
+    specs: Vec<WordSpec>,
+    extra_copies: Vec<bool>,
+    over_limit: Vec<WordSpec>,
+    order: Vec<usize>,
+    outcomes: Vec<(usize, Result<PlacedWord, FailureReason>)>,
+    puzzle: Vec<Vec<char>>,
+    rng: StdRng,
+    attempts: usize,
+}
+
+impl Generation {
+    pub fn new(width: i16, height: i16, words: &[&str], options: &PuzzleOptions, seed: u64) -> Generation {
+        let specs = words.iter().map(|word| WordSpec::from(*word)).collect::<Vec<WordSpec>>();
+        Generation::with_specs(width, height, &specs, options, seed)
+    }
+
+    /// Like `new`, but each word is only placed in its own allowed directions and as many times
+    /// as its `count`. Words past `options.max_words` aren't tried and are reported as not placed.
+    pub fn with_specs(width: i16, height: i16, specs: &[WordSpec], options: &PuzzleOptions, seed: u64) -> Generation {
+        let (specs, extra_copies, over_limit, puzzle) = if specs.iter().any(|spec| !spec.text.trim().is_empty()) {
+            let mut unique_specs = dedupe_specs(specs);
+            let over_limit = match options.max_words {
+                Some(max_words) if unique_specs.len() > max_words => unique_specs.split_off(max_words),
+                _ => Vec::new(),
+            };
+            let (specs, extra_copies) = spec_copies(&unique_specs);
+            let mut puzzle = generate_empty_puzzle(width, height);
+            apply_mask(&mut puzzle, options);
+            (specs, extra_copies, over_limit, puzzle)
+        } else {
+            (Vec::new(), Vec::new(), Vec::new(), Vec::new())
+        };
+        Generation {
+            width,
+            height,
+            options: options.clone(),
+            order: placement_order(&specs, options),
+            specs,
+            extra_copies,
+            over_limit,
+            outcomes: Vec::new(),
+            puzzle,
+            rng: StdRng::seed_from_u64(seed),
+            attempts: 0,
+        }
+    }
+
+    /// The number of words tried so far and the total number of words.
+    pub fn progress(&self) -> (usize, usize) {
+        (self.outcomes.len(), self.specs.len())
+    }
+
+    /// Whether every word has been tried.
+    pub fn is_done(&self) -> bool {
+        self.outcomes.len() == self.specs.len()
+    }
+
+    /// Tries to place up to `count` more words.
+    pub fn place_next(&mut self, count: usize) {
+        let next = self.order.iter()
+            .skip(self.outcomes.len())
+            .take(count)
+            .copied()
+            .collect::<Vec<usize>>();
+        for index in next {
+            let outcome = place_copy(&mut self.puzzle, &self.specs[index], self.extra_copies[index], &self.options, &mut self.rng, &mut self.attempts);
+            self.outcomes.push((index, outcome));
+        }
+    }
+
+    /// Places any words left, then fills the rest of the grid.
+    pub fn finish(mut self) -> (Vec<Vec<char>>, Vec<PlacedWord>, Vec<PlacementFailure>, GenerationStats) {
+        if self.specs.is_empty() {
+            return (Vec::new(), Vec::new(), Vec::new(), GenerationStats::default());
+        }
+        self.place_next(self.specs.len());
+
+        let mut puzzle = self.puzzle;
+        let (mut placed_words, mut failures) = split_outcomes(&self.specs, self.outcomes);
+        if self.options.require_diagonal && !placed_words.is_empty() && !placed_words.iter().any(|placed_word| placed_word.direction.is_diagonal()) {
+            let mut empty_puzzle = generate_empty_puzzle(self.width, self.height);
+            apply_mask(&mut empty_puzzle, &self.options);
+            if let Some(retry) = place_with_a_diagonal(&empty_puzzle, &self.specs, &self.extra_copies, &self.options, &mut self.rng, &mut self.attempts, failures.len()) {
+                ((puzzle, placed_words), failures) = retry;
+            }
+        }
+        fill_puzzle(&mut puzzle, &placed_words, &self.options, &mut self.rng);
+        failures.extend(self.over_limit.iter().map(|spec| PlacementFailure { word: spec.text.clone(), reason: FailureReason::OverWordLimit }));
+
+        let stats = GenerationStats { attempts: self.attempts, placed: placed_words.len(), failed: failures.len() };
+        (puzzle, placed_words, failures, stats)
+    }
+}
+
+#[cfg(test)]
+mod generation_tests {
+    use super::*;
+
+    #[test]
+    fn it_matches_generating_in_one_go() {
+        let words = ["apple", "banana", "cherry", "date", "fig", "grape", "kiwi"];
+        let options = PuzzleOptions { require_diagonal: true, ..PuzzleOptions::default() };
+        for seed in 0..5 {
+            let mut generation = Generation::new(10, 10, &words, &options, seed);
+            let mut chunks = 0;
+            while !generation.is_done() {
+                generation.place_next(3);
+                chunks += 1;
+            }
+            assert_eq!(chunks, 3);
+            assert_eq!(generation.progress(), (7, 7));
+            assert_eq!(generation.finish(), generate_puzzle_with_stats(10, 10, &words, &options, seed));
+        }
+    }
+
+    #[test]
+    fn it_places_the_remaining_words_when_finished_early() {
+        let mut generation = Generation::new(8, 8, &["cat", "dog", "owl"], &PuzzleOptions::default(), 3);
+        generation.place_next(1);
+        assert_eq!(generation.progress(), (1, 3));
+        let (puzzle, placed, failures, _) = generation.finish();
+        assert_eq!(puzzle.len(), 8);
+        assert_eq!(placed.len() + failures.len(), 3);
+    }
+
+    #[test]
+    fn it_is_done_at_once_without_words() {
+        let generation = Generation::new(5, 5, &[" "], &PuzzleOptions::default(), 1);
+        assert!(generation.is_done());
+        assert!(generation.finish().0.is_empty());
+    }
+}
+
+/// Whether any of `words` isn't blank. Without words there is nothing to hide, so no grid is
+/// generated rather than one that is entirely filler.
+fn has_words(words: &[&str]) -> bool {
+    words.iter().any(|word| !word.trim().is_empty())
+}
+
+#[cfg(test)]
+mod empty_word_list_tests {
+    use super::*;
+
+    #[test]
+    fn it_returns_no_grid_without_words() {
+        for words in [vec![], vec!["", "  ", "\t"]] {
+            let (puzzle, placed, failures) = generate_puzzle_with_options(5, 5, &words, &PuzzleOptions::default(), 1);
+            assert!(puzzle.is_empty());
+            assert!(placed.is_empty());
+            assert!(failures.is_empty());
+        }
+    }
+
+    #[test]
+    fn it_returns_no_grid_without_word_specs() {
+        let (puzzle, _, _) = generate_puzzle_with_specs(5, 5, &[WordSpec::from(" ")], &PuzzleOptions::default(), 1);
+        assert!(puzzle.is_empty());
+    }
+}
+
+/// Places the words again on a copy of `empty_puzzle` with one of them restricted to the diagonal
+/// directions, trying each word in turn. Returns the first layout that has a diagonal word and
+/// doesn't fail more words than `max_failures`.
+fn place_with_a_diagonal<R: Rng>(empty_puzzle: &[Vec<char>], specs: &[WordSpec], extra_copies: &[bool], options: &PuzzleOptions, rng: &mut R, attempts: &mut usize, max_failures: usize) -> Option<(GeneratedPuzzle, Vec<PlacementFailure>)> {
+    for index in 0..specs.len() {
+        let word_directions = if specs[index].allowed_directions.is_empty() { options.allowed_directions() } else { &specs[index].allowed_directions };
+        let diagonals = word_directions.iter()
+            .filter(|direction| direction.is_diagonal())
+            .copied()
+            .collect::<Vec<Direction>>();
+        if diagonals.is_empty() {
+            continue;
+        }
+
+        let mut diagonal_specs = specs.to_vec();
+        diagonal_specs[index].allowed_directions = diagonals;
+        let mut puzzle = empty_puzzle.to_vec();
+        let (placed_words, failures) = place_copies(&mut puzzle, &diagonal_specs, extra_copies, options, rng, attempts, &mut |_, _| {});
+        if failures.len() <= max_failures && placed_words.iter().any(|placed_word| placed_word.direction.is_diagonal()) {
+            return Some(((puzzle, placed_words), failures));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod require_diagonal_tests {
+    use super::*;
+
+    #[test]
+    fn it_places_at_least_one_diagonal_word() {
+        let options = PuzzleOptions {
+            directions: vec![Direction::Right, Direction::Down, Direction::DownRight],
+            require_diagonal: true,
+            ..PuzzleOptions::default()
+        };
+        for seed in 0..50 {
+            let (_, placed, failures) = generate_puzzle_with_options(8, 8, &["cat", "dog", "owl"], &options, seed);
+            assert!(failures.is_empty());
+            assert!(placed.iter().any(|placed_word| placed_word.direction.is_diagonal()));
+        }
+    }
+
+    #[test]
+    fn it_keeps_the_layout_when_no_diagonal_is_allowed() {
+        let options = PuzzleOptions { directions: vec![Direction::Right], require_diagonal: true, ..PuzzleOptions::default() };
+        let (_, placed, failures) = generate_puzzle_with_options(5, 5, &["cat", "dog"], &options, 1);
+        assert!(failures.is_empty());
+        assert!(placed.iter().all(|placed_word| placed_word.direction == Direction::Right));
+    }
+}
+
+#[cfg(test)]
+mod generation_stats_tests {
+    use super::*;
+
+    #[test]
+    fn it_uses_few_attempts_for_an_easy_word() {
+        let (_, _, _, stats) = generate_puzzle_with_stats(10, 10, &["cat"], &PuzzleOptions::default(), 4);
+        assert!(stats.attempts >= 1);
+        assert!(stats.attempts <= Direction::ALL.len());
+        assert_eq!(stats.placed, 1);
+        assert_eq!(stats.failed, 0);
+    }
+
+    #[test]
+    fn it_reports_progress_after_each_word() {
+        let mut progress = Vec::new();
+        let (_, placed, failures) = generate_puzzle_with_progress(6, 6, &["cat", "dog", "cat", "elephant"], &PuzzleOptions::default(), 2, |done, total| progress.push((done, total)));
+        assert_eq!(progress, vec![(1, 3), (2, 3), (3, 3)]);
+        assert_eq!(placed.len() + failures.len(), 3);
+    }
+
+    #[test]
+    fn it_counts_failed_words() {
+        let options = PuzzleOptions { directions: vec![Direction::Right], allow_overlap: false, ..PuzzleOptions::default() };
+        let (_, _, _, stats) = generate_puzzle_with_stats(3, 1, &["cat", "dog", "elephant"], &options, 4);
+        assert_eq!(stats.placed, 1);
+        assert_eq!(stats.failed, 2);
+        assert!(stats.attempts > 1);
+    }
+}
+
+/// A word to hide along with the directions it may run in. An empty `allowed_directions` means
+/// the word uses the directions from `PuzzleOptions`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct WordSpec {
+    pub text: String,
+    pub allowed_directions: Vec<Direction>,
+    /// How many times to hide the word. Each copy is a separate placement, and each copy that
+    /// can't be placed is reported as its own failure.
+    pub count: usize,
+}
+
+impl From<&str> for WordSpec {
+    fn from(text: &str) -> Self {
+        WordSpec { text: text.to_string(), allowed_directions: Vec::new(), count: 1 }
+    }
+}
+
+/// How many copies of `spec` are among the `placed` words.
+pub fn copies_placed(spec: &WordSpec, placed: &[PlacedWord]) -> usize {
+    let text = strip_word(&spec.text).to_lowercase();
+    placed.iter()
+        .filter(|placed_word| strip_word(&placed_word.word).to_lowercase() == text)
+        .count()
+}
+
+/// Like `generate_puzzle_with_options`, but each word can be restricted to its own directions.
+pub fn generate_puzzle_with_specs(width: i16, height: i16, specs: &[WordSpec], options: &PuzzleOptions, seed: u64) -> (Vec<Vec<char>>, Vec<PlacedWord>, Vec<PlacementFailure>) {
+    let (puzzle, placed_words, failures, _) = Generation::with_specs(width, height, specs, options, seed).finish();
+    (puzzle, placed_words, failures)
+}
+
+#[cfg(test)]
+mod word_spec_tests {
+    use super::*;
+
+    #[test]
+    fn it_only_places_a_constrained_word_in_its_directions() {
+        let specs = vec![
+            WordSpec { text: "theme".to_string(), allowed_directions: vec![Direction::Right], count: 1 },
+            WordSpec::from("apple"),
+            WordSpec::from("pear"),
+        ];
+        for seed in 0..50 {
+            let (_, placed, failures) = generate_puzzle_with_specs(8, 8, &specs, &PuzzleOptions::default(), seed);
+            assert!(failures.is_empty());
+            assert_eq!(placed[0].word, "THEME");
+            assert_eq!(placed[0].direction, Direction::Right);
+        }
+    }
+
+    #[test]
+    fn it_uses_the_global_directions_for_unconstrained_words() {
+        let specs = vec![
+            WordSpec { text: "cat".to_string(), allowed_directions: vec![Direction::Left], count: 1 },
+            WordSpec::from("dog"),
+        ];
+        let options = PuzzleOptions { directions: vec![Direction::Down], ..PuzzleOptions::default() };
+        let (_, placed, _) = generate_puzzle_with_specs(5, 5, &specs, &options, 3);
+        assert_eq!(placed.iter().map(|placed_word| placed_word.direction).collect::<Vec<Direction>>(), vec![Direction::Left, Direction::Down]);
+    }
+
+    #[test]
+    fn it_places_each_requested_copy() {
+        let specs = vec![
+            WordSpec { count: 2, ..WordSpec::from("cat") },
+            WordSpec::from("dog"),
+        ];
+        for seed in 0..10 {
+            let (puzzle, placed, failures) = generate_puzzle_with_specs(8, 8, &specs, &PuzzleOptions::default(), seed);
+            assert!(failures.is_empty());
+            assert_eq!(copies_placed(&specs[0], &placed), 2);
+            assert_eq!(copies_placed(&specs[1], &placed), 1);
+            let cats = placed.iter().filter(|placed_word| placed_word.word == "CAT").collect::<Vec<&PlacedWord>>();
+            assert_ne!(cats[0].coordinates(), cats[1].coordinates());
+            assert!(count_occurrences(&puzzle, "cat") >= 2);
+        }
+    }
+
+    #[test]
+    fn it_reports_each_copy_that_did_not_fit() {
+        let specs = vec![WordSpec { count: 3, ..WordSpec::from("cat") }];
+        let options = PuzzleOptions { directions: vec![Direction::Right], ..PuzzleOptions::default() };
+        let (_, placed, failures) = generate_puzzle_with_specs(3, 2, &specs, &options, 1);
+        assert_eq!(copies_placed(&specs[0], &placed), 2);
+        assert_eq!(failures, vec![PlacementFailure { word: "cat".to_string(), reason: FailureReason::NoSpaceFound }]);
+    }
+    #[test]
+    fn it_matches_generating_from_plain_words() {
+        let words = ["apple", "banana", "Apple", "cherry"];
+        let specs = words.iter().map(|word| WordSpec::from(*word)).collect::<Vec<WordSpec>>();
+        let options = PuzzleOptions { require_diagonal: true, ..PuzzleOptions::default() };
+        for seed in 0..5 {
+            assert_eq!(generate_puzzle_with_specs(9, 9, &specs, &options, seed), generate_puzzle_with_options(9, 9, &words, &options, seed));
+        }
+    }
+
+    #[test]
+    fn it_only_places_up_to_max_words() {
+        let specs = vec![WordSpec::from("cat"), WordSpec::from("CAT"), WordSpec::from("dog"), WordSpec::from("owl")];
+        let options = PuzzleOptions { max_words: Some(2), ..PuzzleOptions::default() };
+        let (_, placed, failures) = generate_puzzle_with_specs(6, 6, &specs, &options, 2);
+        assert_eq!(placed.iter().map(|placed_word| placed_word.word.as_str()).collect::<Vec<&str>>(), vec!["CAT", "DOG"]);
+        assert_eq!(failures, vec![PlacementFailure { word: "owl".to_string(), reason: FailureReason::OverWordLimit }]);
+
+        let (_, placed, failures) = generate_puzzle_with_options(6, 6, &["cat", "CAT", "dog", "owl"], &options, 2);
+        assert_eq!(placed.iter().map(|placed_word| placed_word.word.as_str()).collect::<Vec<&str>>(), vec!["CAT", "DOG"]);
+        assert_eq!(failures, vec![PlacementFailure { word: "owl".to_string(), reason: FailureReason::OverWordLimit }]);
+    }
+}
+
+/// The largest width or height `try_generate_puzzle` accepts. Bigger grids render too many cells
+/// for the browser to stay responsive.
+pub const MAX_DIMENSION: i16 = 50;
+
+/// Everything produced by a successful call to `try_generate_puzzle`.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PuzzleOutput {
+    pub puzzle: Vec<Vec<char>>,
+    pub placed_words: Vec<PlacedWord>,
+    /// Words that were valid but could not be fitted into the grid.
+    pub failures: Vec<PlacementFailure>,
+    /// Words past `PuzzleOptions::max_words` that were left out without trying to place them.
+    pub skipped: Vec<String>,
+}
+
+/// Why no puzzle could be generated at all.
+#[derive(Debug, PartialEq, Clone)]
+pub enum GenerateError {
+    /// The width or height is zero or negative.
+    InvalidDimensions { width: i16, height: i16 },
+    /// The width or height is larger than `MAX_DIMENSION`.
+    DimensionsTooLarge { width: i16, height: i16 },
+    /// The word list is empty or only has blank lines.
+    NoWords,
+    /// At least one word is longer than any line in the allowed directions; `minimum` is the
+    /// length of the longest word.
+    WordsTooLong { minimum: i16 },
+    /// Some words could not be placed. Only returned by `generate_puzzle_strict`.
+    WordsNotPlaced(Vec<PlacementFailure>),
+}
+
+impl fmt::Display for GenerateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GenerateError::InvalidDimensions { .. } => write!(f, "Width and height must both be at least 1"),
+            GenerateError::DimensionsTooLarge { .. } => write!(f, "Width and height can be at most {}", MAX_DIMENSION),
+            GenerateError::NoWords => write!(f, "Enter at least one word"),
+            GenerateError::WordsTooLong { minimum } => write!(f, "Width or height must be at least {} to fit every word", minimum),
+            GenerateError::WordsNotPlaced(failures) => {
+                let words = failures.iter().map(|failure| failure.word.as_str()).collect::<Vec<&str>>();
+                write!(f, "Could not place: {}", words.join(", "))
+            }
+        }
+    }
+}
+
+impl std::error::Error for GenerateError {}
+
+/// Like `generate_puzzle_with_options`, but checks the dimensions and words first and returns an
+/// error instead of an empty or unfillable grid.
+pub fn try_generate_puzzle(width: i16, height: i16, words: &[&str], options: &PuzzleOptions, seed: u64) -> Result<PuzzleOutput, GenerateError> {
+    let (generation, skipped) = try_start_generation(width, height, words, options, seed)?;
+    let (puzzle, placed_words, failures, _) = generation.finish();
+    Ok(PuzzleOutput { puzzle, placed_words, failures, skipped })
+}
+
+/// Checks the inputs like `try_generate_puzzle`, but returns a `Generation` to be placed a few
+/// words at a time, along with any words left out because of `options.max_words`.
+pub fn try_start_generation(width: i16, height: i16, words: &[&str], options: &PuzzleOptions, seed: u64) -> Result<(Generation, Vec<String>), GenerateError> {
+    check_inputs(width, height, words)?;
+    let (words, skipped) = cap_words(words, options.max_words);
+    let minimum = minimum_dimension(&words);
+    if minimum as usize > longest_path_within(width as usize, height as usize, options.allowed_directions(), options.wrap) {
+        return Err(GenerateError::WordsTooLong { minimum });
+    }
+    Ok((Generation::new(width, height, &words, options, seed), skipped))
+}
+
+/// Like `try_generate_puzzle`, but every word must be placed. If any can't be, no grid is
+/// returned and the error lists each word that failed and why.
+pub fn generate_puzzle_strict(width: i16, height: i16, words: &[&str], options: &PuzzleOptions, seed: u64) -> Result<PuzzleOutput, GenerateError> {
+    check_inputs(width, height, words)?;
+    let (words, skipped) = cap_words(words, options.max_words);
+
+    let (puzzle, placed_words, failures) = generate_puzzle_with_options(width, height, &words, options, seed);
+    if !failures.is_empty() {
+        return Err(GenerateError::WordsNotPlaced(failures));
+    }
+    Ok(PuzzleOutput { puzzle, placed_words, failures, skipped })
+}
+
+/// Splits the non-blank `words` into the first `max_words` and the rest. `None` keeps every word.
+pub fn cap_words<'a>(words: &[&'a str], max_words: Option<usize>) -> (Vec<&'a str>, Vec<String>) {
+    let mut used = words.iter()
+        .copied()
+        .filter(|word| !word.trim().is_empty())
+        .collect::<Vec<&str>>();
+    let skipped = match max_words {
+        Some(max_words) if used.len() > max_words => used.split_off(max_words).iter().map(|word| word.to_string()).collect(),
+        _ => Vec::new(),
+    };
+    (used, skipped)
+}
+
+fn check_inputs(width: i16, height: i16, words: &[&str]) -> Result<(), GenerateError> {
+    if width <= 0 || height <= 0 {
+        return Err(GenerateError::InvalidDimensions { width, height });
+    }
+    if width > MAX_DIMENSION || height > MAX_DIMENSION {
+        return Err(GenerateError::DimensionsTooLarge { width, height });
+    }
+    if words.iter().all(|word| word.trim().is_empty()) {
+        return Err(GenerateError::NoWords);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod generate_error_tests {
+    use super::*;
+
+    #[test]
+    fn it_rejects_non_positive_dimensions() {
+        let result = try_generate_puzzle(0, 5, &["cat"], &PuzzleOptions::default(), 1);
+        assert_eq!(result, Err(GenerateError::InvalidDimensions { width: 0, height: 5 }));
+    }
+
+    #[test]
+    fn it_rejects_dimensions_above_the_maximum() {
+        let result = try_generate_puzzle(MAX_DIMENSION + 1, 10, &["cat"], &PuzzleOptions::default(), 1);
+        assert_eq!(result, Err(GenerateError::DimensionsTooLarge { width: MAX_DIMENSION + 1, height: 10 }));
+        assert!(try_generate_puzzle(MAX_DIMENSION, MAX_DIMENSION, &["cat"], &PuzzleOptions::default(), 1).is_ok());
+    }
+
+    #[test]
+    fn it_rejects_an_empty_word_list() {
+        assert_eq!(try_generate_puzzle(5, 5, &[], &PuzzleOptions::default(), 1), Err(GenerateError::NoWords));
+        assert_eq!(try_generate_puzzle(5, 5, &["", "  "], &PuzzleOptions::default(), 1), Err(GenerateError::NoWords));
+    }
+
+    #[test]
+    fn it_rejects_words_longer_than_the_grid() {
+        let result = try_generate_puzzle(3, 4, &["cat", "horse"], &PuzzleOptions::default(), 1);
+        assert_eq!(result, Err(GenerateError::WordsTooLong { minimum: 5 }));
+        assert_eq!(result.unwrap_err().to_string(), "Width or height must be at least 5 to fit every word");
+    }
+
+    #[test]
+    fn it_rejects_words_longer_than_the_allowed_directions() {
+        let across = PuzzleOptions { directions: vec![Direction::Right], ..PuzzleOptions::default() };
+        assert_eq!(try_generate_puzzle(3, 6, &["cat", "horse"], &across, 1), Err(GenerateError::WordsTooLong { minimum: 5 }));
+        let down = PuzzleOptions { directions: vec![Direction::Down], ..PuzzleOptions::default() };
+        assert_eq!(try_generate_puzzle(3, 6, &["cat", "horse"], &down, 1).unwrap().placed_words.len(), 2);
+    }
+
+    #[test]
+    fn it_accepts_words_that_only_fit_with_wrap() {
+        let options = PuzzleOptions { directions: vec![Direction::DownRight], wrap: true, ..PuzzleOptions::default() };
+        let output = try_generate_puzzle(3, 2, &["abcdef"], &options, 1).unwrap();
+        assert_eq!(output.placed_words.len(), 1);
+        let without_wrap = PuzzleOptions { wrap: false, ..options };
+        assert_eq!(try_generate_puzzle(3, 2, &["abcdef"], &without_wrap, 1), Err(GenerateError::WordsTooLong { minimum: 6 }));
+    }
+
+    #[test]
+    fn it_fails_in_strict_mode_when_a_word_is_too_long() {
+        let result = generate_puzzle_strict(4, 4, &["cat", "elephant", "dog"], &PuzzleOptions::default(), 1);
+        let expected = vec![PlacementFailure { word: "elephant".to_string(), reason: FailureReason::TooLongForGrid }];
+        assert_eq!(result, Err(GenerateError::WordsNotPlaced(expected)));
+        assert_eq!(result.unwrap_err().to_string(), "Could not place: elephant");
+    }
+
+    #[test]
+    fn it_succeeds_in_strict_mode_when_every_word_is_placed() {
+        let output = generate_puzzle_strict(5, 5, &["cat", "dog"], &PuzzleOptions::default(), 1).unwrap();
+        assert_eq!(output.placed_words.len(), 2);
+        assert!(output.failures.is_empty());
+    }
+
+    #[test]
+    fn it_matches_the_tuple_output_when_successful() {
+        let words = vec!["cat", "dog"];
+        let output = try_generate_puzzle(5, 5, &words, &PuzzleOptions::default(), 9).unwrap();
+        let (puzzle, placed_words, failures) = generate_puzzle_with_options(5, 5, &words, &PuzzleOptions::default(), 9);
+        assert_eq!(output, PuzzleOutput { puzzle, placed_words, failures, skipped: Vec::new() });
+    }
+
+    #[test]
+    fn it_skips_words_past_the_cap() {
+        let options = PuzzleOptions { max_words: Some(2), ..PuzzleOptions::default() };
+        let output = try_generate_puzzle(5, 5, &["cat", "", "dog", "owl", "elephant"], &options, 1).unwrap();
+        assert_eq!(output.skipped, vec!["owl".to_string(), "elephant".to_string()]);
+        assert_eq!(output.placed_words.iter().map(|placed_word| placed_word.word.as_str()).collect::<Vec<&str>>(), vec!["CAT", "DOG"]);
+        assert!(output.failures.is_empty());
+    }
+}
+
+/// A generated grid along with where its words were placed.
+pub type GeneratedPuzzle = (Vec<Vec<char>>, Vec<PlacedWord>);
+
+/// Generates `count` puzzles from the same words, seeding each one with `seed_base` plus its
+/// index so every puzzle has a different layout.
+pub fn generate_puzzles(count: usize, width: i16, height: i16, words: &[&str], options: &PuzzleOptions, seed_base: u64) -> Vec<GeneratedPuzzle> {
+    (0..count)
+        .map(|index| {
+            let seed = seed_base.wrapping_add(index as u64);
+            let (puzzle, placed_words, _) = generate_puzzle_with_options(width, height, words, options, seed);
+            (puzzle, placed_words)
+        })
+        .collect()
+}
+
+/// The most placements `generate_puzzle_backtracking` will try before giving up on finding a
+/// layout that fits every word.
+pub const BACKTRACKING_STEP_LIMIT: usize = 10_000;
+
+/// Generates a puzzle by searching for a layout that fits every word, undoing earlier placements
+/// when a later word has nowhere to go. If no such layout is found within
+/// `BACKTRACKING_STEP_LIMIT` placements, the puzzle is generated as `generate_puzzle_with_options`
+/// would.
+pub fn generate_puzzle_backtracking(width: i16, height: i16, words: &Vec<&str>, options: &PuzzleOptions, seed: u64) -> (Vec<Vec<char>>, Vec<PlacedWord>, Vec<PlacementFailure>) {
+    if !has_words(words) {
+        return (Vec::new(), Vec::new(), Vec::new());
+    }
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut puzzle = generate_empty_puzzle(width, height);
+    apply_mask(&mut puzzle, options);
+
+    let mut failures = Vec::new();
+    let mut prepared = Vec::new();
+    for (index, word) in dedupe_words(words).iter().enumerate() {
+        match prepare_word(&puzzle, word, options) {
+            Ok(cased_word) => prepared.push((index, cased_word)),
+            Err(reason) => failures.push(PlacementFailure { word: word.to_string(), reason }),
+        }
+    }
+    if options.sort_by_length {
+        prepared.sort_by_key(|(_, word)| Reverse(word.chars().count()));
+    }
+
+    let mut placed = Vec::with_capacity(prepared.len());
+    let mut steps = 0;
+    if !place_words_backtracking(&mut puzzle, &prepared, options, &mut rng, &mut steps, &mut placed) {
+        return generate_puzzle_with_options(width, height, words, options, seed);
+    }
+
+    placed.sort_by_key(|(index, _)| *index);
+    let placed_words = placed.into_iter().map(|(_, placed_word)| placed_word).collect::<Vec<PlacedWord>>();
+    fill_puzzle(&mut puzzle, &placed_words, options, &mut rng);
+
+    (puzzle, placed_words, failures)
+}
+
+fn place_words_backtracking<R: Rng>(puzzle: &mut [Vec<char>], words: &[(usize, String)], options: &PuzzleOptions, rng: &mut R, steps: &mut usize, placed: &mut Vec<(usize, PlacedWord)>) -> bool {
+    let Some((index, word)) = words.first() else {
+        return true;
+    };
+
+    let mut candidates = Vec::new();
+    for row in 0..puzzle.len() as i16 {
+        for column in 0..puzzle[0].len() as i16 {
+            for direction in options.allowed_directions() {
+                let coordinate = Coordinate::new(row, column);
+                if word_fits_with_options(puzzle, &coordinate, direction, word, options) {
+                    candidates.push(PlacedWord { word: word.clone(), start: coordinate, direction: *direction });
+                }
+            }
+        }
+    }
+    candidates.shuffle(rng);
+
+    for candidate in candidates {
+        if *steps >= BACKTRACKING_STEP_LIMIT {
+            return false;
+        }
+        *steps += 1;
+
+        let previous = candidate.coordinates_in(puzzle).iter()
+            .map(|coordinate| puzzle[coordinate.row as usize][coordinate.column as usize])
+            .collect::<Vec<char>>();
+        place_word(puzzle, &candidate.start, &candidate.direction, word);
+        placed.push((*index, candidate));
+
+        if place_words_backtracking(puzzle, &words[1..], options, rng, steps, placed) {
+            return true;
+        }
+
+        let (_, candidate) = placed.pop().unwrap();
+        for (coordinate, cell) in candidate.coordinates_in(puzzle).iter().zip(previous) {
+            puzzle[coordinate.row as usize][coordinate.column as usize] = cell;
+        }
+    }
+    false
+}
+
+fn fill_puzzle<R: Rng>(puzzle: &mut [Vec<char>], placed_words: &[PlacedWord], options: &PuzzleOptions, rng: &mut R) {
+    match options.fill_mode {
+        FillMode::Random | FillMode::LanguageFrequency(_) => {
+            fill_randomly(puzzle, placed_words, options, rng);
+            remove_accidental_words(puzzle, placed_words, options, rng);
+            break_filler_runs(puzzle, placed_words, options, rng);
+        }
+        FillMode::Blank(filler) => fill_empty_spots(puzzle, filler),
+        FillMode::None => {}
+    }
+    clear_holes(puzzle);
+}
+
+fn fill_randomly<R: Rng>(puzzle: &mut [Vec<char>], placed_words: &[PlacedWord], options: &PuzzleOptions, rng: &mut R) {
+    if let FillMode::LanguageFrequency(language) = options.fill_mode {
+        remove_empty_spots_language(puzzle, language, rng);
+    } else if options.weighted_filler {
+        let placed = placed_words.iter().map(|placed_word| placed_word.word.as_str()).collect::<Vec<&str>>();
+        remove_empty_spots_weighted(puzzle, &placed, rng);
+    } else {
+        remove_empty_spots(puzzle, &filler_charset(options, placed_words), rng);
+    }
+}
+
+/// Refills filler that happens to spell one of the placed words somewhere else in the grid, so
+/// each word has a single answer. Gives up after `options.accidental_word_retries` refills.
+fn remove_accidental_words<R: Rng>(puzzle: &mut [Vec<char>], placed_words: &[PlacedWord], options: &PuzzleOptions, rng: &mut R) {
+    let words = placed_words.iter().map(|placed_word| placed_word.word.as_str()).collect::<Vec<&str>>();
+    let word_cells = placed_words.iter()
+        .flat_map(|placed_word| placed_word.coordinates_in(puzzle))
+        .collect::<HashSet<Coordinate>>();
+
+    for _ in 0..options.accidental_word_retries {
+        let found = if options.wrap { find_words_wrapping(puzzle, &words) } else { find_words(puzzle, &words) };
+        let filler_cells = found.iter()
+            .flat_map(|found| found.coordinates_in(puzzle))
+            .filter(|coordinate| !word_cells.contains(coordinate))
+            .collect::<HashSet<Coordinate>>();
+        if filler_cells.is_empty() {
+            return;
+        }
+        for coordinate in filler_cells {
+            puzzle[coordinate.row as usize][coordinate.column as usize] = ' ';
+        }
+        fill_randomly(puzzle, placed_words, options, rng);
+    }
+}
+
+/// How many times `break_filler_runs` refills before giving up.
+const FILLER_RUN_RETRIES: usize = 20;
+
+/// Refills filler in runs of the same letter longer than `options.max_filler_run`, across rows,
+/// columns and diagonals, so the grid doesn't show things like "QQQQ". Word letters are never
+/// changed, so a run made only of word letters is left alone.
+fn break_filler_runs<R: Rng>(puzzle: &mut [Vec<char>], placed_words: &[PlacedWord], options: &PuzzleOptions, rng: &mut R) {
+    let Some(max_run) = options.max_filler_run else {
+        return;
+    };
+    let word_cells = placed_words.iter()
+        .flat_map(|placed_word| placed_word.coordinates_in(puzzle))
+        .collect::<HashSet<Coordinate>>();
+
+    for _ in 0..FILLER_RUN_RETRIES {
+        let filler_cells = long_runs(puzzle, max_run.max(1)).into_iter()
+            .filter(|coordinate| !word_cells.contains(coordinate))
+            .collect::<Vec<Coordinate>>();
+        if filler_cells.is_empty() {
+            return;
+        }
+        for coordinate in filler_cells {
+            puzzle[coordinate.row as usize][coordinate.column as usize] = ' ';
+        }
+        fill_randomly(puzzle, placed_words, options, rng);
+    }
+}
+
+/// Every cell in a run of more than `max_run` identical letters in any direction. Holes are not
+/// letters, so they never form a run.
+fn long_runs(puzzle: &[Vec<char>], max_run: usize) -> HashSet<Coordinate> {
+    let mut cells = HashSet::new();
+    for (row, letters) in puzzle.iter().enumerate() {
+        for (column, &letter) in letters.iter().enumerate() {
+            if letter == HOLE {
+                continue;
+            }
+            let start = Coordinate::new(row as i16, column as i16);
+            for direction in [Direction::Right, Direction::Down, Direction::DownRight, Direction::DownLeft] {
+                let mut run = vec![start];
+                let mut next = start.get_next_coordinate(&direction);
+                while cell_at(puzzle, &next) == Some(letter) {
+                    run.push(next);
+                    next = next.get_next_coordinate(&direction);
+                }
+                if run.len() > max_run {
+                    cells.extend(run);
+                }
+            }
+        }
+    }
+    cells
+}
+
+#[cfg(test)]
+mod filler_run_tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_runs_longer_than_the_maximum() {
+        let puzzle = vec![
+            vec!['A', 'A', 'A', 'B'],
+            vec!['C', 'B', 'D', 'E'],
+            vec!['B', 'F', 'G', 'H'],
+        ];
+        assert_eq!(long_runs(&puzzle, 2), HashSet::from([Coordinate::new(0, 0), Coordinate::new(0, 1), Coordinate::new(0, 2)]));
+        assert_eq!(long_runs(&puzzle, 3), HashSet::new());
+        assert!(long_runs(&puzzle, 1).contains(&Coordinate::new(2, 0)));
+    }
+
+    #[test]
+    fn it_leaves_no_filler_runs_longer_than_the_maximum() {
+        let options = PuzzleOptions { max_filler_run: Some(1), ..PuzzleOptions::default() };
+        for seed in 0..10 {
+            let (puzzle, placed, _) = generate_puzzle_with_options(10, 10, &["cat", "dog", "bee"], &options, seed);
+            let word_cells = placed.iter()
+                .flat_map(|placed_word| placed_word.coordinates())
+                .collect::<HashSet<Coordinate>>();
+            assert!(long_runs(&puzzle, 1).iter().all(|coordinate| word_cells.contains(coordinate)));
+        }
+    }
+
+    #[test]
+    fn it_never_changes_word_letters() {
+        let options = PuzzleOptions { max_filler_run: Some(1), directions: vec![Direction::Right], ..PuzzleOptions::default() };
+        let (puzzle, placed, failures) = generate_puzzle_with_options(4, 1, &["zzzz"], &options, 1);
+        assert!(failures.is_empty());
+        assert_eq!(puzzle, vec![vec!['Z', 'Z', 'Z', 'Z']]);
+        assert_eq!(placed.len(), 1);
+    }
+
+    #[test]
+    fn it_leaves_masked_cells_blank() {
+        let mut mask = vec![vec![true; 8]; 8];
+        for row in mask.iter_mut().take(4) {
+            row[..4].fill(false);
+        }
+        let options = PuzzleOptions { mask: Some(mask), max_filler_run: Some(1), ..PuzzleOptions::default() };
+        for seed in 0..10 {
+            let (puzzle, _, _) = generate_puzzle_with_options(8, 8, &["cat", "dog"], &options, seed);
+            assert!(puzzle.iter().take(4).all(|row| row[..4].iter().all(|cell| *cell == ' ')));
+        }
+    }
+}
+
+/// The charset to fill the puzzle with. When casing is preserved the filler follows the casing of
+/// the placed words, mixing upper and lowercase letters if the words do.
+fn filler_charset(options: &PuzzleOptions, placed_words: &[PlacedWord]) -> CharsetOptions {
+    if !options.preserve_case || matches!(options.charset, CharsetOptions::Custom(_)) {
+        return options.charset.clone();
+    }
+
+    let letters = placed_words.iter().flat_map(|placed_word| placed_word.word.chars()).filter(|letter| letter.is_alphabetic());
+    let (has_upper, has_lower) = letters.fold((false, false), |(upper, lower), letter| {
+        (upper || letter.is_uppercase(), lower || letter.is_lowercase())
+    });
+    match (has_upper, has_lower) {
+        (true, true) => CharsetOptions::Custom(('A'..='Z').chain('a'..='z').collect()),
+        (false, true) => CharsetOptions::Lowercase,
+        (true, false) => CharsetOptions::Uppercase,
+        (false, false) => options.charset.clone(),
+    }
+}
+
+/// Removes repeated words, ignoring case and surrounding whitespace. The first occurrence of
+/// each word is kept.
+pub fn dedupe_words(words: &[&str]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    words.iter()
+        .filter(|word| seen.insert(word.trim().to_lowercase()))
+        .map(|word| word.to_string())
+        .collect()
+}
+
+/// Like `dedupe_words`, but keeps the first spec for each word.
+fn dedupe_specs(specs: &[WordSpec]) -> Vec<WordSpec> {
+    let mut seen = HashSet::new();
+    specs.iter()
+        .filter(|spec| seen.insert(spec.text.trim().to_lowercase()))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod dedupe_tests {
+    use super::*;
+
+    #[test]
+    fn it_removes_duplicate_words_ignoring_case() {
+        assert_eq!(dedupe_words(&["cat", "CAT", "dog"]), vec!["cat", "dog"]);
+        assert_eq!(dedupe_words(&["Dog", "cat ", "dog", "cat"]), vec!["Dog", "cat "]);
+    }
+}
+
+/// Maps placement failures back onto the given words, preserving their order.
+fn failed_words<'a>(words: &'a Vec<&'a str>, failures: &[PlacementFailure]) -> Vec<&'a str> {
+    let mut failures = failures.iter().peekable();
+    words.iter()
+        .filter(|word| match failures.peek() {
+            Some(failure) if failure.word == **word => {
+                failures.next();
+                true
+            }
+            _ => false,
+        })
+        .copied()
+        .collect()
+}
+
+/// Settings controlling how words are placed in the puzzle.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PuzzleOptions {
+    /// The directions words may run in. Empty means all directions.
+    pub directions: Vec<Direction>,
+    /// Whether words may cross each other where they share the same letter.
+    pub allow_overlap: bool,
+    /// How many positions `PlacementStrategy::Scan` tries for each word before giving up on it.
+    /// `None` tries every position and direction in the grid.
+    pub max_attempts_per_word: Option<usize>,
+    /// Whether filler letters are drawn from the letters of the placed words rather than A-Z.
+    /// Only used with `FillMode::Random`.
+    pub weighted_filler: bool,
+    /// What goes in the cells that no word passes through.
+    pub fill_mode: FillMode,
+    /// How many times random filler that accidentally spells one of the words is replaced
+    /// before giving up. Zero skips the check.
+    pub accidental_word_retries: usize,
+    /// The longest run of the same filler letter allowed in any direction. Longer runs are
+    /// refilled a limited number of times. `None` allows any run. Only used with random filler.
+    pub max_filler_run: Option<usize>,
+    /// The alphabet used for filler letters and the casing applied to words.
+    pub charset: CharsetOptions,
+    /// Whether words are placed exactly as given instead of being recased for the charset.
+    pub preserve_case: bool,
+    /// Whether the longest words are placed first, so short words don't take up the room they need.
+    pub sort_by_length: bool,
+    /// The shape of the puzzle. Cells that are `false` are holes: no word passes through them
+    /// and they are left blank. Cells outside the mask are part of the puzzle.
+    pub mask: Option<Vec<Vec<bool>>>,
+    /// Cells that stay blank, for example to leave room for a picture. No word passes through
+    /// them. Cells outside the grid are ignored.
+    pub protected_cells: Vec<Coordinate>,
+    /// How many cells along each edge of the grid are kept free of words, leaving a frame of
+    /// filler around them. `0` lets words reach the edges.
+    pub border_margin: i16,
+    /// How a position is chosen for each word.
+    pub placement_strategy: PlacementStrategy,
+    /// How likely each direction is to be tried first for a word, in the order of
+    /// `Direction::ALL`. The weights don't need to add up to anything. `None` picks evenly.
+    pub direction_weights: Option<[f32; 8]>,
+    /// Whether `PlacementStrategy::Scan` tries the directions at each position in a random order
+    /// for every word instead of always following the same cycle.
+    pub shuffle_directions: bool,
+    /// Whether to place the words again with one of them forced onto a diagonal when none of
+    /// them ended up diagonal, so the puzzle isn't all straight lines.
+    pub require_diagonal: bool,
+    /// The most words used. `try_generate_puzzle` returns the words past it as skipped, and the
+    /// other generators report them as not placed with `FailureReason::OverWordLimit`. `None`
+    /// uses every word.
+    pub max_words: Option<usize>,
+    /// Whether words that run off one edge of the grid continue from the opposite edge. A word
+    /// still may not pass through the same cell twice, so it can't be longer than the grid.
+    pub wrap: bool,
+}
+
+impl Default for PuzzleOptions {
+    fn default() -> Self {
+        PuzzleOptions {
+            directions: Direction::ALL.to_vec(),
+            allow_overlap: true,
+            max_attempts_per_word: None,
+            weighted_filler: false,
+            fill_mode: FillMode::default(),
+            accidental_word_retries: 0,
+            max_filler_run: None,
+            charset: CharsetOptions::default(),
+            preserve_case: false,
+            sort_by_length: true,
+            mask: None,
+            protected_cells: Vec::new(),
+            border_margin: 0,
+            placement_strategy: PlacementStrategy::default(),
+            direction_weights: None,
+            shuffle_directions: false,
+            require_diagonal: false,
+            max_words: None,
+            wrap: false,
+        }
+    }
+}
+
+impl PuzzleOptions {
+    fn allowed_directions(&self) -> &[Direction] {
+        if self.directions.is_empty() {
+            &Direction::ALL
+        } else {
+            &self.directions
+        }
+    }
+}
+
+/// How `add_word_to_puzzle` looks for a position for each word.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum PlacementStrategy {
+    /// Start somewhere random, then scan every position row by row until the word fits.
+    /// Always finds a position if there is one, but tends to cluster words together.
+    #[default]
+    Scan,
+    /// Try this many random positions and directions, giving up on the word if none fit.
+    Random { attempts: usize },
+}
+
+#[cfg(test)]
+mod placement_strategy_tests {
+    use super::*;
+
+    #[test]
+    fn it_places_words_at_random_positions() {
+        let options = PuzzleOptions { placement_strategy: PlacementStrategy::Random { attempts: 100 }, ..PuzzleOptions::default() };
+        let words = vec!["cat", "dog", "owl"];
+        let (puzzle, placed, failures) = generate_puzzle_with_options(8, 8, &words, &options, 6);
+        assert!(failures.is_empty());
+        assert_eq!(find_words(&puzzle, &words).len(), placed.len());
+    }
+
+    #[test]
+    fn it_reports_words_when_random_attempts_run_out() {
+        let options = PuzzleOptions { placement_strategy: PlacementStrategy::Random { attempts: 0 }, ..PuzzleOptions::default() };
+        let (_, placed, failures) = generate_puzzle_with_options(8, 8, &["cat"], &options, 6);
+        assert!(placed.is_empty());
+        assert_eq!(failures, vec![PlacementFailure { word: "cat".to_string(), reason: FailureReason::NoSpaceFound }]);
+    }
+}
+
+/// The set of characters used to fill the puzzle.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub enum CharsetOptions {
+    /// Uppercase A-Z. Words are uppercased.
+    #[default]
+    Uppercase,
+    /// Lowercase a-z. Words are lowercased.
+    Lowercase,
+    /// Filler is drawn from the given characters. Words are placed as given.
+    Custom(Vec<char>),
+}
+
+impl CharsetOptions {
+    fn apply_case(&self, word: &str) -> String {
+        match self {
+            CharsetOptions::Uppercase => word.to_uppercase(),
+            CharsetOptions::Lowercase => word.to_lowercase(),
+            CharsetOptions::Custom(_) => word.to_string(),
+        }
+    }
+
+    fn random_character<R: Rng>(&self, rng: &mut R) -> char {
+        match self {
+            CharsetOptions::Uppercase => generate_random_character(rng),
+            CharsetOptions::Lowercase => generate_random_character(rng).to_ascii_lowercase(),
+            CharsetOptions::Custom(alphabet) => alphabet.choose(rng).copied()
+                .unwrap_or_else(|| generate_random_character(rng)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod charset_tests {
+    use super::*;
+
+    #[test]
+    fn it_cases_words_for_the_charset() {
+        assert_eq!(CharsetOptions::Uppercase.apply_case("Cat"), "CAT");
+        assert_eq!(CharsetOptions::Lowercase.apply_case("Cat"), "cat");
+        assert_eq!(CharsetOptions::Custom(vec!['x']).apply_case("Cat"), "Cat");
+    }
+
+    #[test]
+    fn it_generates_characters_from_the_charset() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..100 {
+            assert!(CharsetOptions::Uppercase.random_character(&mut rng).is_ascii_uppercase());
+            assert!(CharsetOptions::Lowercase.random_character(&mut rng).is_ascii_lowercase());
+            assert!(['α', 'β'].contains(&CharsetOptions::Custom(vec!['α', 'β']).random_character(&mut rng)));
+        }
+    }
+}
+
+/// How the cells left empty after placing words are filled.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum FillMode {
+    /// Random letters from the charset, hiding the words.
+    #[default]
+    Random,
+    /// The given character, such as '.' or '_', leaving a template that shows only the words.
+    Blank(char),
+    /// Empty cells are left as spaces.
+    None,
+    /// Random uppercase letters drawn as often as they appear in the language, so the filler
+    /// blends in with words from it.
+    LanguageFrequency(Language),
+}
+
+/// A language with a built-in table of how often each letter is used.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Language {
+    English,
+    Spanish,
+    German,
+}
+
+/// Letter frequencies in English text, in percent.
+const ENGLISH_LETTER_FREQUENCIES: [(char, f32); 26] = [
+    ('A', 8.2), ('B', 1.5), ('C', 2.8), ('D', 4.3), ('E', 12.7), ('F', 2.2), ('G', 2.0),
+    ('H', 6.1), ('I', 7.0), ('J', 0.15), ('K', 0.77), ('L', 4.0), ('M', 2.4), ('N', 6.7),
+    ('O', 7.5), ('P', 1.9), ('Q', 0.095), ('R', 6.0), ('S', 6.3), ('T', 9.1), ('U', 2.8),
+    ('V', 0.98), ('W', 2.4), ('X', 0.15), ('Y', 2.0), ('Z', 0.074),
+];
+
+/// Letter frequencies in Spanish text, in percent.
+const SPANISH_LETTER_FREQUENCIES: [(char, f32); 27] = [
+    ('A', 11.5), ('B', 2.2), ('C', 4.0), ('D', 5.0), ('E', 12.2), ('F', 0.7), ('G', 1.8),
+    ('H', 0.7), ('I', 6.2), ('J', 0.5), ('K', 0.01), ('L', 5.0), ('M', 3.2), ('N', 6.7),
+    ('Ñ', 0.3), ('O', 8.7), ('P', 2.5), ('Q', 0.9), ('R', 6.9), ('S', 8.0), ('T', 4.6),
+    ('U', 2.9), ('V', 0.9), ('W', 0.02), ('X', 0.2), ('Y', 0.9), ('Z', 0.5),
+];
+
+/// Letter frequencies in German text, in percent. ß is left out since it has no common
+/// uppercase form.
+const GERMAN_LETTER_FREQUENCIES: [(char, f32); 29] = [
+    ('A', 6.5), ('Ä', 0.5), ('B', 1.9), ('C', 2.7), ('D', 5.1), ('E', 17.4), ('F', 1.7),
+    ('G', 3.0), ('H', 4.8), ('I', 7.6), ('J', 0.3), ('K', 1.2), ('L', 3.4), ('M', 2.5),
+    ('N', 9.8), ('O', 2.5), ('Ö', 0.3), ('P', 0.8), ('Q', 0.02), ('R', 7.0), ('S', 7.3),
+    ('T', 6.2), ('U', 4.4), ('Ü', 0.7), ('V', 0.7), ('W', 1.9), ('X', 0.03), ('Y', 0.04),
+    ('Z', 1.1),
+];
+
+impl Language {
+    /// Each uppercase letter of the language and how often it is used.
+    pub fn letter_frequencies(&self) -> &'static [(char, f32)] {
+        match self {
+            Language::English => &ENGLISH_LETTER_FREQUENCIES,
+            Language::Spanish => &SPANISH_LETTER_FREQUENCIES,
+            Language::German => &GERMAN_LETTER_FREQUENCIES,
+        }
+    }
+}
+
+/// Fills empty spots with uppercase letters sampled by how often they are used in `language`.
+pub fn remove_empty_spots_language<R: Rng>(puzzle: &mut [Vec<char>], language: Language, rng: &mut R) {
+    let frequencies = language.letter_frequencies();
+    let distribution = WeightedIndex::new(frequencies.iter().map(|(_, frequency)| *frequency)).unwrap();
+    for cell in puzzle.iter_mut().flat_map(|row| row.iter_mut()) {
+        if *cell == ' ' {
+            *cell = frequencies[distribution.sample(rng)].0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod language_tests {
+    use super::*;
+
+    fn filler(language: Language) -> Vec<char> {
+        let mut puzzle = generate_empty_puzzle(40, 40);
+        remove_empty_spots_language(&mut puzzle, language, &mut StdRng::seed_from_u64(6));
+        puzzle.into_iter().flatten().collect()
+    }
+
+    #[test]
+    fn it_only_samples_letters_from_the_language() {
+        assert!(filler(Language::Spanish).contains(&'Ñ'));
+        assert!(!filler(Language::English).contains(&'Ñ'));
+        assert!(filler(Language::English).iter().all(|letter| letter.is_ascii_uppercase()));
+        assert!(filler(Language::German).iter().any(|letter| ['Ä', 'Ö', 'Ü'].contains(letter)));
+    }
+
+    #[test]
+    fn it_samples_common_letters_more_often() {
+        let letters = filler(Language::English);
+        let count = |wanted: char| letters.iter().filter(|letter| **letter == wanted).count();
+        assert!(count('E') > count('Z'));
+    }
+
+    #[test]
+    fn it_fills_generated_puzzles_in_the_language() {
+        let options = PuzzleOptions { fill_mode: FillMode::LanguageFrequency(Language::Spanish), ..PuzzleOptions::default() };
+        let (puzzle, placed, _) = generate_puzzle_with_options(6, 6, &["año", "gato"], &options, 3);
+        assert_eq!(placed.len(), 2);
+        assert!(puzzle.iter().flatten().all(|cell| cell.is_alphabetic() && cell.is_uppercase()));
+    }
+}
+
+#[cfg(test)]
+mod fill_mode_tests {
+    use super::*;
+
+    fn generate_with_fill_mode(fill_mode: FillMode) -> Vec<Vec<char>> {
+        let options = PuzzleOptions { directions: vec![Direction::Right], fill_mode, ..PuzzleOptions::default() };
+        let (puzzle, placed, _) = generate_puzzle_with_options(3, 2, &["cat"], &options, 4);
+        assert_eq!(placed.len(), 1);
+        puzzle
+    }
+
+    fn count_cells(puzzle: &[Vec<char>], predicate: impl Fn(char) -> bool) -> usize {
+        puzzle.iter().flatten().filter(|cell| predicate(**cell)).count()
+    }
+
+    #[test]
+    fn it_fills_with_random_letters() {
+        let puzzle = generate_with_fill_mode(FillMode::Random);
+        assert_eq!(count_cells(&puzzle, |cell| cell.is_ascii_uppercase()), 6);
+    }
+
+    #[test]
+    fn it_fills_with_a_blank_character() {
+        let puzzle = generate_with_fill_mode(FillMode::Blank('.'));
+        assert_eq!(count_cells(&puzzle, |cell| cell == '.'), 3);
+        assert!(puzzle.contains(&vec!['C', 'A', 'T']));
+    }
+
+    #[test]
+    fn it_leaves_empty_cells_unfilled() {
+        let puzzle = generate_with_fill_mode(FillMode::None);
+        assert_eq!(count_cells(&puzzle, |cell| cell == ' '), 3);
+        assert!(puzzle.contains(&vec!['C', 'A', 'T']));
+    }
+
+    #[test]
+    fn it_replaces_filler_that_spells_a_word() {
+        let words = ["CAT"];
+        let options = PuzzleOptions { charset: CharsetOptions::Custom(vec!['C', 'A', 'T', 'X']), ..PuzzleOptions::default() };
+        let (puzzle, _, _) = generate_puzzle_with_options(6, 6, &words, &options, 12);
+        assert!(find_words(&puzzle, &words).len() > 1);
+
+        let options = PuzzleOptions { accidental_word_retries: 200, ..options };
+        for seed in 0..10 {
+            let (puzzle, placed, _) = generate_puzzle_with_options(6, 6, &words, &options, seed);
+            assert_eq!(find_words(&puzzle, &words), placed);
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Difficulty {
+    /// Words only read left-to-right and top-to-bottom.
+    Easy,
+    /// Words may also read diagonally, as long as they read left-to-right.
+    Medium,
+    /// Words may run in any direction, including backwards, and cross each other.
+    Hard,
+}
+
+impl Difficulty {
+    /// The directions words may be placed in at this difficulty.
+    pub fn direction_set(&self) -> Vec<Direction> {
+        match self {
+            Difficulty::Easy => vec![Direction::Right, Direction::Down],
+            Difficulty::Medium => vec![Direction::Right, Direction::Down, Direction::DownRight, Direction::UpRight],
+            Difficulty::Hard => Direction::ALL.to_vec(),
+        }
+    }
+
+    pub fn options(&self) -> PuzzleOptions {
+        PuzzleOptions {
+            directions: self.direction_set(),
+            allow_overlap: *self == Difficulty::Hard,
+            ..PuzzleOptions::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod difficulty_tests {
+    use super::*;
+
+    #[test]
+    fn it_maps_difficulties_to_options() {
+        let easy = Difficulty::Easy.options();
+        assert_eq!(easy.directions, Difficulty::Easy.direction_set());
+        assert!(!easy.allow_overlap);
+
+        assert_eq!(Difficulty::Medium.options().directions, Difficulty::Medium.direction_set());
+        assert!(!Difficulty::Medium.options().allow_overlap);
+        assert_eq!(Difficulty::Hard.options().directions, Direction::ALL.to_vec());
+        assert!(Difficulty::Hard.options().allow_overlap);
+    }
+
+    #[test]
+    fn it_allows_more_directions_at_each_difficulty() {
+        assert_eq!(Difficulty::Easy.direction_set(), vec![Direction::Right, Direction::Down]);
+        assert_eq!(Difficulty::Medium.direction_set(), vec![Direction::Right, Direction::Down, Direction::DownRight, Direction::UpRight]);
+        assert_eq!(Difficulty::Hard.direction_set(), Direction::ALL.to_vec());
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub enum Direction {
+    Up = 1,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 8] = [
+        Direction::Up,
+        Direction::Down,
+        Direction::Left,
+        Direction::Right,
+        Direction::UpLeft,
+        Direction::UpRight,
+        Direction::DownLeft,
+        Direction::DownRight,
+    ];
+
+    pub(crate) fn from_number(number: u8) -> Option<Direction> {
+        match number {
+            1 => Some(Direction::Up),
+            2 => Some(Direction::Down),
+            3 => Some(Direction::Left),
+            4 => Some(Direction::Right),
+            5 => Some(Direction::UpLeft),
+            6 => Some(Direction::UpRight),
+            7 => Some(Direction::DownLeft),
+            8 => Some(Direction::DownRight),
+            _ => None,
+        }
+    }
+
+    /// Whether the direction runs across both rows and columns.
+    pub fn is_diagonal(&self) -> bool {
+        matches!(self, Direction::UpLeft | Direction::UpRight | Direction::DownLeft | Direction::DownRight)
+    }
+
+    /// Whether the direction runs against normal reading order, upwards or to the left.
+    pub fn is_reversed(&self) -> bool {
+        matches!(self, Direction::Up | Direction::Left | Direction::UpLeft | Direction::UpRight | Direction::DownLeft)
+    }
+
+    /// The direction after turning the grid a quarter turn clockwise.
+    pub fn rotated_90(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+            Direction::UpRight => Direction::DownRight,
+            Direction::DownRight => Direction::DownLeft,
+            Direction::DownLeft => Direction::UpLeft,
+            Direction::UpLeft => Direction::UpRight,
+        }
+    }
+
+    /// The direction after flipping the grid left to right.
+    pub fn mirrored_horizontal(&self) -> Direction {
+        match self {
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+            Direction::UpLeft => Direction::UpRight,
+            Direction::UpRight => Direction::UpLeft,
+            Direction::DownLeft => Direction::DownRight,
+            Direction::DownRight => Direction::DownLeft,
+            Direction::Up | Direction::Down => *self,
+        }
+    }
+
+    /// The direction after swapping the grid's rows and columns.
+    pub fn transposed(&self) -> Direction {
+        match self {
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Right,
+            Direction::Left => Direction::Up,
+            Direction::Up => Direction::Left,
+            Direction::UpRight => Direction::DownLeft,
+            Direction::DownLeft => Direction::UpRight,
+            Direction::UpLeft | Direction::DownRight => *self,
+        }
+    }
+
+    fn get_next_direction(&self) -> Direction {
+        Direction::from_number((*self as u8) + 1).unwrap_or(Direction::Up)
+    }
+
+    fn get_next_allowed_direction(&self, allowed: &[Direction]) -> Direction {
+        let mut direction = self.get_next_direction();
+        while !allowed.contains(&direction) && direction != *self {
+            direction = direction.get_next_direction();
+        }
+        direction
+    }
+}
+
+#[cfg(test)]
+mod direction_tests {
+    use super::*;
+
+    #[test]
+    fn it_knows_the_next_direction() {
+        assert_eq!(Direction::Up.get_next_direction(), Direction::Down);
+        assert_eq!(Direction::DownRight.get_next_direction(), Direction::Up);
+    }
+
+    #[test]
+    fn it_skips_directions_that_are_not_allowed() {
+        let allowed = [Direction::Down, Direction::Right];
+        assert_eq!(Direction::Down.get_next_allowed_direction(&allowed), Direction::Right);
+        assert_eq!(Direction::Right.get_next_allowed_direction(&allowed), Direction::Down);
+        assert_eq!(Direction::Right.get_next_allowed_direction(&[Direction::Right]), Direction::Right);
+    }
+
+    #[test]
+    fn it_can_create_directions_from_numbers() {
+        assert_eq!(Direction::from_number(1), Some(Direction::Up));
+        assert_eq!(Direction::from_number(2), Some(Direction::Down));
+        assert_eq!(Direction::from_number(8), Some(Direction::DownRight));
+        assert_eq!(Direction::from_number(9), None);
+    }
+}
+
+
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct Coordinate {
+    pub row: i16,
+    pub column: i16,
+}
+
+
+
+impl Coordinate {
+    pub fn new(row: i16, column: i16) -> Coordinate {
+        Coordinate {
+            row,
+            column,
+        }
+    }
+
+    fn get_next_coordinate(&self, direction: &Direction) -> Coordinate {
+        match direction {
+            Direction::Up => Coordinate::new(self.row - 1, self.column),
+            Direction::Down => Coordinate::new(self.row + 1, self.column),
+            Direction::Left => Coordinate::new(self.row, self.column - 1),
+            Direction::Right => Coordinate::new(self.row, self.column + 1),
+            Direction::UpLeft => Coordinate::new(self.row - 1, self.column - 1),
+            Direction::UpRight => Coordinate::new(self.row - 1, self.column + 1),
+            Direction::DownLeft => Coordinate::new(self.row + 1, self.column - 1),
+            Direction::DownRight => Coordinate::new(self.row + 1, self.column + 1),
+        }
+    }
+
+    fn wrapped(&self, width: i16, height: i16) -> Coordinate {
+        Coordinate::new(self.row.rem_euclid(height), self.column.rem_euclid(width))
+    }
+
+    fn get_next_coordinate_for_size(&self, width: i16, height: i16) -> Coordinate {
+        if self.column + 1 < width {
+            Coordinate::new(self.row, self.column + 1)
+        } else if self.row + 1 < height {
+            Coordinate::new(self.row + 1, 0)
+        } else {
+            Coordinate::new(0, 0)
+        }
+    }
+
+    fn valid(&self, width: i16, height: i16) -> bool {
+        self.row >= 0 && self.row < height && self.column >= 0 && self.column < width
+    }
+}
+
+#[cfg(test)]
+mod coordinate_tests {
+    use super::*;
+
+    #[test]
+    fn it_can_get_the_next_coordinate() {
+        assert_eq!(Coordinate::new(1, 3), Coordinate::new(1, 2).get_next_coordinate(&Direction::Right));
+        assert_eq!(Coordinate::new(3, 3), Coordinate::new(2, 2).get_next_coordinate(&Direction::DownRight));
+    }
+
+    #[test]
+    fn it_can_get_the_next_coordinate_for_the_size() {
+        assert_eq!(Coordinate::new(0, 1), Coordinate::new(0, 0).get_next_coordinate_for_size(2, 2));
+        assert_eq!(Coordinate::new(1, 0), Coordinate::new(0, 1).get_next_coordinate_for_size(2, 2));
+        assert_eq!(Coordinate::new(1, 1), Coordinate::new(1, 0).get_next_coordinate_for_size(2, 2));
+        assert_eq!(Coordinate::new(0, 0), Coordinate::new(1, 1).get_next_coordinate_for_size(2, 2));
+    }
+
+    #[test]
+    fn it_knows_if_a_coordinate_is_valid() {
+        assert!(Coordinate::new(0, 0).valid(2, 2));
+        assert!(Coordinate::new(1, 1).valid(2, 2));
+        assert!(!Coordinate::new(2, 2).valid(2, 2));
+        assert!(!Coordinate::new(-1, 0).valid(2, 2));
+        assert!(!Coordinate::new(0, -1).valid(2, 2));
+    }
+
+    #[test]
+    fn it_reads_cells_safely() {
+        let puzzle = vec![vec!['A', 'B'], vec!['C', 'D']];
+        assert_eq!(cell_at(&puzzle, &Coordinate::new(0, 0)), Some('A'));
+        assert_eq!(cell_at(&puzzle, &Coordinate::new(1, 0)), Some('C'));
+        assert_eq!(cell_at(&puzzle, &Coordinate::new(2, 0)), None);
+        assert_eq!(cell_at(&puzzle, &Coordinate::new(0, -1)), None);
+        assert_eq!(cell_at(&[], &Coordinate::new(0, 0)), None);
+    }
+}
+
+/// The character at `coordinate`, or `None` if it is outside the puzzle.
+pub fn cell_at(puzzle: &[Vec<char>], coordinate: &Coordinate) -> Option<char> {
+    let width = puzzle.first().map_or(0, |row| row.len()) as i16;
+    if !coordinate.valid(width, puzzle.len() as i16) {
+        return None;
+    }
+    puzzle[coordinate.row as usize].get(coordinate.column as usize).copied()
+}
+
+/// A word that was placed in the puzzle, starting at `start` and running in `direction`.
+#[derive(Debug, PartialEq, Clone)]
+#[cfg_attr(feature = "json", derive(serde::Serialize, serde::Deserialize))]
+pub struct PlacedWord {
+    pub word: String,
+    pub start: Coordinate,
+    pub direction: Direction,
+}
+
+impl PlacedWord {
+    /// The coordinates of every letter of the word, in order.
+    pub fn coordinates(&self) -> Vec<Coordinate> {
+        let mut coordinates = Vec::with_capacity(self.word.chars().count());
+        let mut coordinate = self.start;
+        for _ in self.word.chars() {
+            coordinates.push(coordinate);
+            coordinate = coordinate.get_next_coordinate(&self.direction);
+        }
+        coordinates
+    }
+
+    /// Like `coordinates`, but wraps around the edges of `puzzle` for words placed with `wrap`.
+    /// For words that don't cross an edge this is the same as `coordinates`.
+    pub fn coordinates_in(&self, puzzle: &[Vec<char>]) -> Vec<Coordinate> {
+        let height = puzzle.len() as i16;
+        let width = puzzle.first().map(|row| row.len()).unwrap_or(0) as i16;
+        self.coordinates_within(width, height)
+    }
+
+    /// Like `coordinates_in`, but for a grid of the given size.
+    pub fn coordinates_within(&self, width: i16, height: i16) -> Vec<Coordinate> {
+        if width == 0 || height == 0 {
+            return self.coordinates();
+        }
+        self.coordinates().iter()
+            .map(|coordinate| coordinate.wrapped(width, height))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod placed_word_tests {
+    use super::*;
+
+    #[test]
+    fn it_knows_the_coordinates_of_its_letters() {
+        let placed = PlacedWord { word: "CAT".to_string(), start: Coordinate::new(2, 2), direction: Direction::UpLeft };
+        assert_eq!(placed.coordinates(), vec![Coordinate::new(2, 2), Coordinate::new(1, 1), Coordinate::new(0, 0)]);
+    }
+}
+
+/// Turns the grid a quarter turn clockwise, so a grid `height` rows tall becomes `height`
+/// columns wide.
+pub fn rotate_90(puzzle: &[Vec<char>]) -> Vec<Vec<char>> {
+    let width = puzzle.first().map(|row| row.len()).unwrap_or(0);
+    (0..width)
+        .map(|column| puzzle.iter().rev().map(|row| row[column]).collect())
+        .collect()
+}
+
+/// Flips the grid left to right.
+pub fn mirror_horizontal(puzzle: &[Vec<char>]) -> Vec<Vec<char>> {
+    puzzle.iter()
+        .map(|row| row.iter().rev().copied().collect())
+        .collect()
+}
+
+/// Swaps the grid's rows and columns, so a grid `width` columns wide becomes `width` rows tall.
+pub fn transpose(puzzle: &[Vec<char>]) -> Vec<Vec<char>> {
+    let width = puzzle.first().map(|row| row.len()).unwrap_or(0);
+    (0..width)
+        .map(|column| puzzle.iter().map(|row| row[column]).collect())
+        .collect()
+}
+
+impl PlacedWord {
+    /// Where the word is after `rotate_90` on a grid `height` rows tall.
+    pub fn rotated_90(&self, height: i16) -> PlacedWord {
+        PlacedWord {
+            word: self.word.clone(),
+            start: Coordinate::new(self.start.column, height - 1 - self.start.row),
+            direction: self.direction.rotated_90(),
+        }
+    }
+
+    /// Where the word is after `mirror_horizontal` on a grid `width` columns wide.
+    pub fn mirrored_horizontal(&self, width: i16) -> PlacedWord {
+        PlacedWord {
+            word: self.word.clone(),
+            start: Coordinate::new(self.start.row, width - 1 - self.start.column),
+            direction: self.direction.mirrored_horizontal(),
+        }
+    }
+
+    /// Where the word is after `transpose`.
+    pub fn transposed(&self) -> PlacedWord {
+        PlacedWord {
+            word: self.word.clone(),
+            start: Coordinate::new(self.start.column, self.start.row),
+            direction: self.direction.transposed(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod transform_tests {
+    use super::*;
+
+    fn letters_at(puzzle: &[Vec<char>], placed_word: &PlacedWord) -> String {
+        placed_word.coordinates_in(puzzle).iter()
+            .map(|coordinate| puzzle[coordinate.row as usize][coordinate.column as usize])
+            .collect()
+    }
+
+    #[test]
+    fn it_rotates_a_grid_clockwise() {
+        let puzzle = vec![
+            vec!['A', 'B', 'C'],
+            vec!['D', 'E', 'F'],
+        ];
+        assert_eq!(rotate_90(&puzzle), vec![
+            vec!['D', 'A'],
+            vec!['E', 'B'],
+            vec!['F', 'C'],
+        ]);
+        assert_eq!(rotate_90(&rotate_90(&rotate_90(&rotate_90(&puzzle)))), puzzle);
+    }
+
+    #[test]
+    fn it_mirrors_a_grid() {
+        let puzzle = vec![
+            vec!['A', 'B', 'C'],
+            vec!['D', 'E', 'F'],
+        ];
+        assert_eq!(mirror_horizontal(&puzzle), vec![
+            vec!['C', 'B', 'A'],
+            vec!['F', 'E', 'D'],
+        ]);
+    }
+
+    #[test]
+    fn it_transposes_a_grid() {
+        let puzzle = vec![
+            vec!['A', 'B', 'C'],
+            vec!['D', 'E', 'F'],
+        ];
+        assert_eq!(transpose(&puzzle), vec![
+            vec!['A', 'D'],
+            vec!['B', 'E'],
+            vec!['C', 'F'],
+        ]);
+        assert_eq!(transpose(&transpose(&puzzle)), puzzle);
+        assert!(transpose(&[]).is_empty());
+    }
+
+    #[test]
+    fn it_moves_placed_words_with_the_grid() {
+        let (puzzle, placed, _) = generate_puzzle_with_options(7, 5, &["cat", "horse", "owl", "dog"], &PuzzleOptions::default(), 11);
+        let rotated = rotate_90(&puzzle);
+        let mirrored = mirror_horizontal(&puzzle);
+        let transposed = transpose(&puzzle);
+        for placed_word in placed {
+            assert_eq!(letters_at(&rotated, &placed_word.rotated_90(5)), placed_word.word);
+            assert_eq!(letters_at(&mirrored, &placed_word.mirrored_horizontal(7)), placed_word.word);
+            assert_eq!(letters_at(&transposed, &placed_word.transposed()), placed_word.word);
+        }
+    }
+}
+
+/// How many of the `placed` words run in each direction. Directions no word uses are left out.
+pub fn direction_histogram(placed: &[PlacedWord]) -> HashMap<Direction, usize> {
+    let mut histogram = HashMap::new();
+    for placed_word in placed {
+        *histogram.entry(placed_word.direction).or_insert(0) += 1;
+    }
+    histogram
+}
+
+#[cfg(test)]
+mod direction_histogram_tests {
+    use super::*;
+
+    #[test]
+    fn it_counts_the_words_in_each_direction() {
+        let placed_word = |direction| PlacedWord { word: "CAT".to_string(), start: Coordinate::new(0, 0), direction };
+        let placed = vec![
+            placed_word(Direction::Right),
+            placed_word(Direction::DownLeft),
+            placed_word(Direction::Right),
+            placed_word(Direction::Up),
+            placed_word(Direction::Right),
+        ];
+        let histogram = direction_histogram(&placed);
+        assert_eq!(histogram.len(), 3);
+        assert_eq!(histogram[&Direction::Right], 3);
+        assert_eq!(histogram[&Direction::DownLeft], 1);
+        assert_eq!(histogram[&Direction::Up], 1);
+        assert_eq!(histogram.get(&Direction::Down), None);
+    }
+
+    #[test]
+    fn it_is_empty_without_words() {
+        assert!(direction_histogram(&[]).is_empty());
+    }
+}
+
+/// The top left and bottom right cells of the smallest rectangle covering every letter of the
+/// `placed` words, or `None` if there are none. Words that wrap around an edge aren't supported.
+pub fn placed_bounding_box(placed: &[PlacedWord]) -> Option<(Coordinate, Coordinate)> {
+    let coordinates = placed.iter()
+        .flat_map(|placed_word| placed_word.coordinates())
+        .collect::<Vec<Coordinate>>();
+    let top = coordinates.iter().map(|coordinate| coordinate.row).min()?;
+    let bottom = coordinates.iter().map(|coordinate| coordinate.row).max()?;
+    let left = coordinates.iter().map(|coordinate| coordinate.column).min()?;
+    let right = coordinates.iter().map(|coordinate| coordinate.column).max()?;
+    Some((Coordinate::new(top, left), Coordinate::new(bottom, right)))
+}
+
+#[cfg(test)]
+mod bounding_box_tests {
+    use super::*;
+
+    #[test]
+    fn it_covers_every_placed_letter() {
+        let placed = vec![
+            PlacedWord { word: "CAT".to_string(), start: Coordinate::new(2, 1), direction: Direction::Right },
+            PlacedWord { word: "DOG".to_string(), start: Coordinate::new(5, 4), direction: Direction::UpLeft },
+        ];
+        assert_eq!(placed_bounding_box(&placed), Some((Coordinate::new(2, 1), Coordinate::new(5, 4))));
+        assert_eq!(placed_bounding_box(&placed[..1]), Some((Coordinate::new(2, 1), Coordinate::new(2, 3))));
+    }
+
+    #[test]
+    fn it_is_none_without_words() {
+        assert_eq!(placed_bounding_box(&[]), None);
+    }
+}
+
+/// Trims the rows and columns of filler around the `placed` words, keeping `margin` cells of
+/// filler on each side where the grid has them. Returns the smaller grid along with the words
+/// moved to match it. Without words, or with words that run off the grid, the grid and words
+/// are returned as they are.
+pub fn crop_to_words(puzzle: &[Vec<char>], placed: &[PlacedWord], margin: i16) -> GeneratedPuzzle {
+    let height = puzzle.len() as i16;
+    let width = puzzle.first().map(|row| row.len()).unwrap_or(0) as i16;
+    let Some((top_left, bottom_right)) = placed_bounding_box(placed) else {
+        return (puzzle.to_vec(), Vec::new());
+    };
+    if top_left.row < 0 || top_left.column < 0 || bottom_right.row >= height || bottom_right.column >= width {
+        return (puzzle.to_vec(), placed.to_vec());
+    }
+    let margin = margin.max(0);
+    let top = (top_left.row - margin).max(0);
+    let left = (top_left.column - margin).max(0);
+    let bottom = (bottom_right.row + margin).min(height - 1);
+    let right = (bottom_right.column + margin).min(width - 1);
+
+    let cropped = puzzle[top as usize..=bottom as usize].iter()
+        .map(|row| row[left as usize..=right as usize].to_vec())
+        .collect();
+    let moved = placed.iter()
+        .map(|placed_word| PlacedWord {
+            start: Coordinate::new(placed_word.start.row - top, placed_word.start.column - left),
+            ..placed_word.clone()
+        })
+        .collect();
+    (cropped, moved)
+}
+
+#[cfg(test)]
+mod crop_tests {
+    use super::*;
+
+    fn sparse_puzzle() -> GeneratedPuzzle {
+        let mut puzzle = generate_empty_puzzle(10, 8);
+        let placed = vec![
+            PlacedWord { word: "CAT".to_string(), start: Coordinate::new(3, 2), direction: Direction::Right },
+            PlacedWord { word: "COW".to_string(), start: Coordinate::new(3, 2), direction: Direction::Down },
+        ];
+        for placed_word in placed.iter() {
+            place_word(&mut puzzle, &placed_word.start, &placed_word.direction, &placed_word.word);
+        }
+        fill_empty_spots(&mut puzzle, '.');
+        (puzzle, placed)
+    }
+
+    #[test]
+    fn it_crops_to_the_words() {
+        let (puzzle, placed) = sparse_puzzle();
+        let (cropped, moved) = crop_to_words(&puzzle, &placed, 0);
+        assert_eq!(cropped, vec![
+            vec!['C', 'A', 'T'],
+            vec!['O', '.', '.'],
+            vec!['W', '.', '.'],
+        ]);
+        assert_eq!(moved[0].start, Coordinate::new(0, 0));
+        assert_eq!(find_words(&cropped, &["cat", "cow"]), moved);
+    }
+
+    #[test]
+    fn it_keeps_a_margin_inside_the_grid() {
+        let (puzzle, placed) = sparse_puzzle();
+        let (cropped, moved) = crop_to_words(&puzzle, &placed, 1);
+        assert_eq!((cropped[0].len(), cropped.len()), (5, 5));
+        assert_eq!(moved[0].start, Coordinate::new(1, 1));
+
+        let (cropped, _) = crop_to_words(&puzzle, &placed, 3);
+        assert_eq!((cropped[0].len(), cropped.len()), (8, 8));
+    }
+
+    #[test]
+    fn it_leaves_a_grid_without_words_alone() {
+        let (puzzle, _) = sparse_puzzle();
+        assert_eq!(crop_to_words(&puzzle, &[], 0), (puzzle, Vec::new()));
+    }
+
+    #[test]
+    fn it_leaves_words_outside_the_grid_alone() {
+        let placed = vec![PlacedWord { word: "CAT".to_string(), start: Coordinate::new(0, 0), direction: Direction::Right }];
+        assert_eq!(crop_to_words(&[], &placed, 0), (Vec::new(), placed.clone()));
+
+        let (puzzle, _) = sparse_puzzle();
+        let off_grid = vec![PlacedWord { word: "CAT".to_string(), start: Coordinate::new(7, 8), direction: Direction::Right }];
+        assert_eq!(crop_to_words(&puzzle, &off_grid, 1), (puzzle, off_grid));
+    }
+}
+
+/// A hash of the grid's letters that stays the same across runs and platforms, for spotting
+/// identical grids, such as two generated from the same seed. Rows are separated so grids with
+/// the same letters in a different shape hash differently.
+pub fn grid_fingerprint(puzzle: &[Vec<char>]) -> u64 {
+    // 64-bit FNV-1a, since the standard library's hasher isn't guaranteed to be stable.
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut text = String::new();
+    for row in puzzle {
+        text.extend(row.iter());
+        text.push('\n');
+    }
+    text.bytes().fold(OFFSET_BASIS, |hash, byte| (hash ^ byte as u64).wrapping_mul(PRIME))
+}
+
+#[cfg(test)]
+mod fingerprint_tests {
+    use super::*;
+
+    #[test]
+    fn it_hashes_identical_grids_equally() {
+        let (first, _, _) = generate_puzzle_with_options(8, 8, &["cat", "dog"], &PuzzleOptions::default(), 5);
+        let (second, _, _) = generate_puzzle_with_options(8, 8, &["cat", "dog"], &PuzzleOptions::default(), 5);
+        assert_eq!(grid_fingerprint(&first), grid_fingerprint(&second));
+        assert_eq!(grid_fingerprint(&[vec!['A']]), 0x0908_9c07_b5a1_4cd2);
+    }
+
+    #[test]
+    fn it_changes_when_a_cell_or_the_shape_changes() {
+        let puzzle = vec![vec!['A', 'B'], vec!['C', 'D']];
+        let mut changed = puzzle.clone();
+        changed[1][0] = 'X';
+        assert_ne!(grid_fingerprint(&puzzle), grid_fingerprint(&changed));
+        assert_ne!(grid_fingerprint(&[vec!['A', 'B', 'C'], vec!['D']]), grid_fingerprint(&[vec!['A', 'B'], vec!['C', 'D']]));
+    }
+}
+
+/// The fraction of cells in the puzzle that belong to a placed word, from 0.0 to 1.0.
+pub fn fill_density(puzzle: &[Vec<char>], placed: &[PlacedWord]) -> f32 {
+    let total_cells = puzzle.iter().map(|row| row.len()).sum::<usize>();
+    if total_cells == 0 {
+        return 0.0;
+    }
+
+    let word_cells = placed.iter()
+        .flat_map(|placed_word| placed_word.coordinates_in(puzzle))
+        .collect::<HashSet<Coordinate>>();
+    word_cells.len() as f32 / total_cells as f32
+}
+
+/// Whether less than `threshold` of the puzzle is word letters, leaving it mostly filler.
+/// An empty puzzle is never sparse.
+pub fn is_sparse(puzzle: &[Vec<char>], placed: &[PlacedWord], threshold: f32) -> bool {
+    !puzzle.is_empty() && fill_density(puzzle, placed) < threshold
+}
+
+#[cfg(test)]
+mod density_tests {
+    use super::*;
+
+    #[test]
+    fn it_computes_the_fill_density() {
+        let puzzle = generate_empty_puzzle(4, 2);
+        let placed = vec![
+            PlacedWord { word: "CAT".to_string(), start: Coordinate::new(0, 0), direction: Direction::Right },
+            PlacedWord { word: "AX".to_string(), start: Coordinate::new(0, 1), direction: Direction::Down },
+        ];
+        assert_eq!(fill_density(&puzzle, &placed), 0.5);
+        assert_eq!(fill_density(&puzzle, &[]), 0.0);
+        assert_eq!(fill_density(&[], &placed), 0.0);
+    }
+
+    #[test]
+    fn it_knows_when_a_grid_is_sparse() {
+        let puzzle = generate_empty_puzzle(4, 2);
+        let placed = vec![PlacedWord { word: "CAT".to_string(), start: Coordinate::new(0, 0), direction: Direction::Right }];
+        assert!(is_sparse(&puzzle, &placed, 0.5));
+        assert!(!is_sparse(&puzzle, &placed, 0.375));
+        assert!(!is_sparse(&[], &placed, 0.5));
+    }
+}
+
+/// Estimates how hard a puzzle is on a scale from 0 to 100. The score adds up:
+///
+/// * up to 30 points for the number of words, 1.5 per word up to 20 words
+/// * up to 20 points for the average word length, 2 per letter up to 10 letters
+/// * up to 30 points for the share of words running diagonally or backwards, with a word that
+///   does both counting twice
+/// * up to 20 points for the share of cells that are filler rather than word letters
+pub fn difficulty_score(puzzle: &[Vec<char>], placed: &[PlacedWord]) -> u32 {
+    if placed.is_empty() {
+        return 0;
+    }
+
+    let word_count = placed.len() as f32;
+    let average_length = placed.iter().map(|placed_word| placed_word.word.chars().count()).sum::<usize>() as f32 / word_count;
+    let twists = placed.iter()
+        .map(|placed_word| placed_word.direction.is_diagonal() as u8 + placed_word.direction.is_reversed() as u8)
+        .sum::<u8>() as f32;
+
+    let score = word_count.min(20.0) * 1.5 +
+        average_length.min(10.0) * 2.0 +
+        twists / (word_count * 2.0) * 30.0 +
+        (1.0 - fill_density(puzzle, placed)) * 20.0;
+    score.round() as u32
+}
+
+#[cfg(test)]
+mod difficulty_score_tests {
+    use super::*;
+
+    #[test]
+    fn it_scores_an_empty_puzzle_as_zero() {
+        assert_eq!(difficulty_score(&generate_empty_puzzle(5, 5), &[]), 0);
+    }
+
+    #[test]
+    fn it_scores_reversed_diagonal_words_higher() {
+        let puzzle = generate_empty_puzzle(5, 5);
+        let cat = PlacedWord { word: "CAT".to_string(), start: Coordinate::new(0, 0), direction: Direction::Right };
+        let dog = PlacedWord { word: "DOG".to_string(), start: Coordinate::new(1, 0), direction: Direction::Right };
+        assert_eq!(difficulty_score(&puzzle, &[cat.clone(), dog]), 3 + 6 + 15);
+
+        let reversed_diagonal = PlacedWord { word: "DOG".to_string(), start: Coordinate::new(4, 4), direction: Direction::UpLeft };
+        assert_eq!(difficulty_score(&puzzle, &[cat, reversed_diagonal]), 3 + 6 + 15 + 15);
+    }
+}
+
+/// A summary of the letters in a word list.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct WordStats {
+    /// The number of letters across every word, ignoring spaces and punctuation.
+    pub total_letters: usize,
+    pub longest_word: String,
+    pub shortest_word: String,
+    /// How many times each letter appears, counted in uppercase.
+    pub letter_frequencies: BTreeMap<char, usize>,
+}
+
+/// Counts the letters in `words` and finds the longest and shortest of them. Blank words are
+/// ignored, and an empty list gives zeroed stats.
+pub fn word_stats(words: &[&str]) -> WordStats {
+    let mut stats = WordStats::default();
+    for word in words.iter().map(|word| word.trim()).filter(|word| !word.is_empty()) {
+        let length = word.chars().count();
+        if stats.longest_word.is_empty() || length > stats.longest_word.chars().count() {
+            stats.longest_word = word.to_string();
+        }
+        if stats.shortest_word.is_empty() || length < stats.shortest_word.chars().count() {
+            stats.shortest_word = word.to_string();
+        }
+        for letter in word.chars().filter(|letter| letter.is_alphabetic()) {
+            stats.total_letters += 1;
+            for upper in letter.to_uppercase() {
+                *stats.letter_frequencies.entry(upper).or_insert(0) += 1;
+            }
+        }
+    }
+    stats
+}
+
+#[cfg(test)]
+mod word_stats_tests {
+    use super::*;
+
+    #[test]
+    fn it_counts_letter_frequencies() {
+        let stats = word_stats(&["Cat", "tact", "", "ox"]);
+        assert_eq!(stats.total_letters, 9);
+        assert_eq!(stats.longest_word, "tact");
+        assert_eq!(stats.shortest_word, "ox");
+        assert_eq!(stats.letter_frequencies, BTreeMap::from([('A', 2), ('C', 2), ('O', 1), ('T', 3), ('X', 1)]));
+    }
+
+    #[test]
+    fn it_returns_zeroed_stats_for_no_words() {
+        assert_eq!(word_stats(&[]), WordStats::default());
+    }
+}
+
+/// Searches `puzzle` in every direction for each of `words`, ignoring case, spaces and hyphens,
+/// and returns every place each word appears. A word found more than once is returned once per match.
+pub fn find_words(puzzle: &[Vec<char>], words: &[&str]) -> Vec<PlacedWord> {
+    search_words(puzzle, words, false)
+}
+
+/// Like `find_words`, but also finds words that run off one edge and continue from the opposite
+/// one, as placed with `PuzzleOptions::wrap`.
+pub fn find_words_wrapping(puzzle: &[Vec<char>], words: &[&str]) -> Vec<PlacedWord> {
+    search_words(puzzle, words, true)
+}
+
+fn search_words(puzzle: &[Vec<char>], words: &[&str], wrap: bool) -> Vec<PlacedWord> {
+    let mut found = Vec::new();
+    for word in words.iter().map(|word| strip_word(word)).filter(|word| !word.is_empty()) {
+        for (row, cells) in puzzle.iter().enumerate() {
+            for column in 0..cells.len() {
+                let start = Coordinate::new(row as i16, column as i16);
+                for direction in Direction::ALL {
+                    if let Some(letters) = read_word(puzzle, &start, &direction, &word, wrap) {
+                        found.push(PlacedWord { word: letters, start, direction });
+                    }
+                }
+            }
+        }
+    }
+    found
+}
+
+/// Returns the letters of `word` as they appear in the grid when it starts at `coordinate` and
+/// runs in `direction`, or `None` if the grid doesn't spell it there.
+fn read_word(puzzle: &[Vec<char>], coordinate: &Coordinate, direction: &Direction, word: &str, wrap: bool) -> Option<String> {
+    let path = word_path(puzzle, coordinate, direction, word.chars().count(), wrap)?;
+    let mut letters = String::with_capacity(word.len());
+    for (letter, coordinate) in word.chars().zip(path) {
+        let cell = cell_at(puzzle, &coordinate)?;
+        if !cell.to_lowercase().eq(letter.to_lowercase()) {
+            return None;
+        }
+        letters.push(cell);
+    }
+    Some(letters)
+}
+
+#[cfg(test)]
+mod solver_tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_words_in_every_direction() {
+        let puzzle = vec![
+            vec!['C', 'A', 'T'],
+            vec!['X', 'O', 'X'],
+            vec!['X', 'X', 'G'],
+        ];
+        let found = find_words(&puzzle, &["cat", "dog", "tac", "COG"]);
+        assert_eq!(found, vec![
+            PlacedWord { word: "CAT".to_string(), start: Coordinate::new(0, 0), direction: Direction::Right },
+            PlacedWord { word: "TAC".to_string(), start: Coordinate::new(0, 2), direction: Direction::Left },
+            PlacedWord { word: "COG".to_string(), start: Coordinate::new(0, 0), direction: Direction::DownRight },
+        ]);
+    }
 
-    let mut puzzle = generate_empty_puzzle(width, height);
-    let words_not_placed = add_words_to_puzzle(&mut puzzle, words);
-    remove_empty_spots(&mut puzzle);
+    #[test]
+    fn it_returns_every_match_of_a_repeated_word() {
+        let puzzle = vec![
+            vec!['A', 'B', 'A'],
+        ];
+        let found = find_words(&puzzle, &["ab"]);
+        assert_eq!(found, vec![
+            PlacedWord { word: "AB".to_string(), start: Coordinate::new(0, 0), direction: Direction::Right },
+            PlacedWord { word: "AB".to_string(), start: Coordinate::new(0, 2), direction: Direction::Left },
+        ]);
+    }
 
-    (puzzle, words_not_placed)
+    #[test]
+    fn it_finds_every_placed_word_in_a_generated_puzzle() {
+        let words = vec!["apple", "banana", "cherry", "date", "elderberry", "fig"];
+        for seed in 0..10 {
+            let (puzzle, placed, _) = generate_puzzle_with_options(12, 12, &words, &PuzzleOptions::default(), seed);
+            let found = find_words(&puzzle, &words);
+            for placed_word in placed.iter() {
+                assert!(found.contains(placed_word), "{:?} was not found", placed_word);
+            }
+        }
+    }
+}
+
+/// Counts how many times `word` appears in `puzzle` in any direction. A palindrome read both
+/// ways over the same cells counts once.
+pub fn count_occurrences(puzzle: &[Vec<char>], word: &str) -> usize {
+    find_words(puzzle, &[word]).iter()
+        .map(|found| {
+            let mut cells = found.coordinates();
+            cells.sort_by_key(|coordinate| (coordinate.row, coordinate.column));
+            cells
+        })
+        .collect::<HashSet<Vec<Coordinate>>>()
+        .len()
+}
+
+/// Returns each of `words` that appears more than once in `puzzle`, such as a short word that
+/// filler happened to spell a second time.
+pub fn duplicated_words(puzzle: &[Vec<char>], words: &[&str]) -> Vec<String> {
+    words.iter()
+        .filter(|word| count_occurrences(puzzle, word) > 1)
+        .map(|word| word.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod occurrence_tests {
+    use super::*;
+
+    #[test]
+    fn it_counts_each_place_a_word_appears() {
+        let puzzle = vec![
+            vec!['C', 'A', 'T', 'X'],
+            vec!['X', 'X', 'X', 'X'],
+            vec!['X', 'T', 'A', 'C'],
+        ];
+        assert_eq!(count_occurrences(&puzzle, "cat"), 2);
+        assert_eq!(count_occurrences(&puzzle, "dog"), 0);
+        assert_eq!(duplicated_words(&puzzle, &["cat", "dog"]), vec!["cat".to_string()]);
+    }
+
+    #[test]
+    fn it_counts_a_palindrome_once() {
+        let puzzle = vec![
+            vec!['A', 'B', 'A'],
+        ];
+        assert_eq!(count_occurrences(&puzzle, "aba"), 1);
+        assert!(duplicated_words(&puzzle, &["aba"]).is_empty());
+    }
 }
 
+/// Two placed words that claim the same cell with different letters.
 #[derive(Debug, PartialEq, Clone, Copy)]
-enum Direction {
-    Up = 1,
-    Down,
-    Left,
-    Right,
-    UpLeft,
-    UpRight,
-    DownLeft,
-    DownRight,
+pub struct Conflict {
+    pub coordinate: Coordinate,
+    /// The letter the earlier word puts in the cell.
+    pub first: char,
+    /// The letter the later word puts in the cell.
+    pub second: char,
 }
 
-impl Direction {
-    fn from_number(number: u8) -> Option<Direction> {
-        match number {
-            1 => Some(Direction::Up),
-            2 => Some(Direction::Down),
-            3 => Some(Direction::Left),
-            4 => Some(Direction::Right),
-            5 => Some(Direction::UpLeft),
-            6 => Some(Direction::UpRight),
-            7 => Some(Direction::DownLeft),
-            8 => Some(Direction::DownRight),
-            _ => None,
+/// Checks that placed words only share cells where they agree on the letter, as overlapping
+/// words must. Words that run off the `width` by `height` grid wrap around its edges. Returns the
+/// first cell where two words disagree.
+pub fn validate_placements(placed: &[PlacedWord], width: i16, height: i16) -> Result<(), Conflict> {
+    let mut claimed: HashMap<Coordinate, char> = HashMap::new();
+    for placed_word in placed {
+        for (letter, coordinate) in placed_word.word.chars().zip(placed_word.coordinates_within(width, height)) {
+            match claimed.get(&coordinate) {
+                Some(&first) if first != letter => return Err(Conflict { coordinate, first, second: letter }),
+                Some(_) => {}
+                None => {
+                    claimed.insert(coordinate, letter);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod validate_placements_tests {
+    use super::*;
+
+    #[test]
+    fn it_accepts_words_sharing_a_letter() {
+        let placed = vec![
+            PlacedWord { word: "CAT".to_string(), start: Coordinate::new(0, 0), direction: Direction::Right },
+            PlacedWord { word: "TOE".to_string(), start: Coordinate::new(0, 2), direction: Direction::Down },
+        ];
+        assert_eq!(validate_placements(&placed, 6, 6), Ok(()));
+    }
+
+    #[test]
+    fn it_reports_a_cell_with_two_letters() {
+        let placed = vec![
+            PlacedWord { word: "CAT".to_string(), start: Coordinate::new(0, 0), direction: Direction::Right },
+            PlacedWord { word: "DOG".to_string(), start: Coordinate::new(0, 1), direction: Direction::Down },
+        ];
+        assert_eq!(validate_placements(&placed, 6, 6), Err(Conflict { coordinate: Coordinate::new(0, 1), first: 'A', second: 'D' }));
+    }
+
+    #[test]
+    fn it_accepts_generated_overlapping_puzzles() {
+        let options = PuzzleOptions { allow_overlap: true, ..PuzzleOptions::default() };
+        for seed in 0..10 {
+            let (_, placed, _) = generate_puzzle_with_options(6, 6, &["apple", "pear", "plum", "grape", "lime", "peach"], &options, seed);
+            assert_eq!(validate_placements(&placed, 6, 6), Ok(()));
+        }
+    }
+
+    #[test]
+    fn it_reports_a_conflict_across_the_edge() {
+        let placed = vec![
+            PlacedWord { word: "CAT".to_string(), start: Coordinate::new(0, 3), direction: Direction::Right },
+            PlacedWord { word: "DOG".to_string(), start: Coordinate::new(0, 0), direction: Direction::Down },
+        ];
+        assert_eq!(validate_placements(&placed, 4, 4), Err(Conflict { coordinate: Coordinate::new(0, 0), first: 'A', second: 'D' }));
+    }
+
+    #[test]
+    fn it_accepts_generated_wrapping_puzzles() {
+        let options = PuzzleOptions { wrap: true, allow_overlap: true, ..PuzzleOptions::default() };
+        for seed in 0..10 {
+            let (_, placed, _) = generate_puzzle_with_options(5, 5, &["apple", "pear", "plum", "grape", "lime", "peach"], &options, seed);
+            assert_eq!(validate_placements(&placed, 5, 5), Ok(()));
+        }
+    }
+}
+
+/// A word that could not be placed in the puzzle and why.
+#[derive(Debug, PartialEq, Clone)]
+pub struct PlacementFailure {
+    pub word: String,
+    pub reason: FailureReason,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum FailureReason {
+    /// The word is longer than both the width and the height of the grid.
+    TooLongForGrid,
+    /// No free path long enough for the word was left in the grid.
+    NoSpaceFound,
+    /// The word has no letters.
+    EmptyWord,
+    /// The word could not be placed across any of the key words placed before it.
+    NoCrossingFound,
+    /// The word came after `PuzzleOptions::max_words` other words, so it wasn't tried.
+    OverWordLimit,
+}
+
+/// Creates a `width` by `height` grid of blanks, or an empty grid if either dimension is not positive.
+pub fn generate_empty_puzzle(width: i16, height: i16) -> Vec<Vec<char>> {
+    if width <= 0 || height <= 0 {
+        return Vec::new();
+    }
+    let mut puzzle: Vec<Vec<char>> = Vec::with_capacity(height as usize);
+    for _ in 0..height {
+        let row = vec![' '; width as usize];
+        puzzle.push(row);
+    }
+    puzzle
+}
+
+#[cfg(test)]
+mod wrap_tests {
+    use super::*;
+
+    fn blocked_row() -> Vec<Vec<char>> {
+        vec![
+            vec![' ', ' ', 'Q', ' ', ' '],
+            vec!['Q', 'Q', 'Q', 'Q', 'Q'],
+        ]
+    }
+
+    #[test]
+    fn it_wraps_a_word_past_the_right_edge() {
+        let options = PuzzleOptions { directions: vec![Direction::Right], wrap: true, ..PuzzleOptions::default() };
+        let mut puzzle = blocked_row();
+        let (placed, failures) = add_words_to_puzzle(&mut puzzle, &["ABCD"], &options, &mut StdRng::seed_from_u64(1));
+
+        assert!(failures.is_empty());
+        assert_eq!(placed[0].start, Coordinate::new(0, 3));
+        assert_eq!(placed[0].coordinates_in(&puzzle), vec![Coordinate::new(0, 3), Coordinate::new(0, 4), Coordinate::new(0, 0), Coordinate::new(0, 1)]);
+        assert_eq!(puzzle[0], vec!['C', 'D', 'Q', 'A', 'B']);
+        assert_eq!(find_words_wrapping(&puzzle, &["abcd"]), placed);
+        assert!(find_words(&puzzle, &["abcd"]).is_empty());
+    }
+
+    #[test]
+    fn it_does_not_wrap_by_default() {
+        let options = PuzzleOptions { directions: vec![Direction::Right], ..PuzzleOptions::default() };
+        let mut puzzle = blocked_row();
+        let (placed, failures) = add_words_to_puzzle(&mut puzzle, &["ABCD"], &options, &mut StdRng::seed_from_u64(1));
+
+        assert!(placed.is_empty());
+        assert_eq!(failures.len(), 1);
+    }
+
+    #[test]
+    fn it_does_not_let_a_wrapped_word_cross_itself() {
+        let puzzle = vec![vec![' '; 3]; 3];
+        assert!(word_fits_wrapping(&puzzle, &Coordinate::new(0, 2), &Direction::Right, "ABC", false));
+        assert!(!word_fits_wrapping(&puzzle, &Coordinate::new(0, 2), &Direction::Right, "ABCA", true));
+    }
+}
+
+/// Marks cells that are outside a non-rectangular puzzle while it is generated.
+pub const HOLE: char = '\0';
+
+/// Marks every cell that is `false` in the options' mask, and every protected cell, as a hole.
+fn apply_mask(puzzle: &mut [Vec<char>], options: &PuzzleOptions) {
+    if let Some(mask) = &options.mask {
+        for (row, mask_row) in puzzle.iter_mut().zip(mask.iter()) {
+            for (cell, open) in row.iter_mut().zip(mask_row.iter()) {
+                if !open {
+                    *cell = HOLE;
+                }
+            }
+        }
+    }
+    for coordinate in options.protected_cells.iter() {
+        if cell_at(puzzle, coordinate).is_some() {
+            puzzle[coordinate.row as usize][coordinate.column as usize] = HOLE;
+        }
+    }
+}
+
+/// Blanks out the holes once the puzzle has been filled.
+fn clear_holes(puzzle: &mut [Vec<char>]) {
+    for cell in puzzle.iter_mut().flat_map(|row| row.iter_mut()) {
+        if *cell == HOLE {
+            *cell = ' ';
+        }
+    }
+}
+
+#[cfg(test)]
+mod mask_tests {
+    use super::*;
+
+    #[test]
+    fn it_never_places_words_across_holes() {
+        let mask = (0..6)
+            .map(|row| (0..6).map(|column| row != 2 && column != 3).collect::<Vec<bool>>())
+            .collect::<Vec<Vec<bool>>>();
+        let options = PuzzleOptions { mask: Some(mask.clone()), ..PuzzleOptions::default() };
+        for seed in 0..20 {
+            let (puzzle, placed, _) = generate_puzzle_with_options(6, 6, &["cat", "dog", "ox", "bee"], &options, seed);
+            for coordinate in placed.iter().flat_map(|placed_word| placed_word.coordinates()) {
+                assert!(mask[coordinate.row as usize][coordinate.column as usize]);
+            }
+            for (row, cells) in puzzle.iter().enumerate() {
+                for (column, cell) in cells.iter().enumerate() {
+                    assert_eq!(*cell == ' ', !mask[row][column]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_fails_words_that_only_fit_across_holes() {
+        let mask = vec![vec![true, true, false, true, true]];
+        let options = PuzzleOptions { mask: Some(mask), ..PuzzleOptions::default() };
+        let (_, placed, failures) = generate_puzzle_with_options(5, 1, &["abc", "de"], &options, 1);
+        assert_eq!(placed.len(), 1);
+        assert_eq!(failures, vec![PlacementFailure { word: "abc".to_string(), reason: FailureReason::NoSpaceFound }]);
+    }
+
+    #[test]
+    fn it_leaves_protected_cells_blank() {
+        let protected_cells = vec![Coordinate::new(1, 1), Coordinate::new(3, 2), Coordinate::new(9, 9)];
+        let options = PuzzleOptions { protected_cells: protected_cells.clone(), ..PuzzleOptions::default() };
+        for seed in 0..20 {
+            let (puzzle, placed, _) = generate_puzzle_with_options(5, 5, &["cat", "dog", "bee"], &options, seed);
+            assert_eq!(puzzle[1][1], ' ');
+            assert_eq!(puzzle[3][2], ' ');
+            assert_eq!(puzzle.iter().flatten().filter(|cell| **cell == ' ').count(), 2);
+            for coordinate in placed.iter().flat_map(|placed_word| placed_word.coordinates()) {
+                assert!(!protected_cells.contains(&coordinate));
+            }
+        }
+    }
+}
+
+pub fn add_words_to_puzzle<R: Rng>(puzzle: &mut [Vec<char>], words: &[&str], options: &PuzzleOptions, rng: &mut R) -> (Vec<PlacedWord>, Vec<PlacementFailure>) {
+    let specs = words.iter().map(|word| WordSpec::from(*word)).collect::<Vec<WordSpec>>();
+    add_word_specs_to_puzzle(puzzle, &specs, options, rng)
+}
+
+/// Like `add_words_to_puzzle`, but each word is only placed in its own allowed directions.
+pub fn add_word_specs_to_puzzle<R: Rng>(puzzle: &mut [Vec<char>], specs: &[WordSpec], options: &PuzzleOptions, rng: &mut R) -> (Vec<PlacedWord>, Vec<PlacementFailure>) {
+    place_word_specs(puzzle, specs, options, rng, &mut 0, &mut |_, _| {})
+}
+
+fn place_word_specs<R: Rng>(puzzle: &mut [Vec<char>], specs: &[WordSpec], options: &PuzzleOptions, rng: &mut R, attempts: &mut usize, on_progress: &mut dyn FnMut(usize, usize)) -> (Vec<PlacedWord>, Vec<PlacementFailure>) {
+    let (copies, extra_copies) = spec_copies(specs);
+    place_copies(puzzle, &copies, &extra_copies, options, rng, attempts, on_progress)
+}
+
+/// Places specs already expanded by `spec_copies`.
+fn place_copies<R: Rng>(puzzle: &mut [Vec<char>], copies: &[WordSpec], extra_copies: &[bool], options: &PuzzleOptions, rng: &mut R, attempts: &mut usize, on_progress: &mut dyn FnMut(usize, usize)) -> (Vec<PlacedWord>, Vec<PlacementFailure>) {
+    let mut outcomes = Vec::with_capacity(copies.len());
+    for index in placement_order(copies, options) {
+        let outcome = place_copy(puzzle, &copies[index], extra_copies[index], options, rng, attempts);
+        outcomes.push((index, outcome));
+        on_progress(outcomes.len(), copies.len());
+    }
+    split_outcomes(copies, outcomes)
+}
+
+fn place_copy<R: Rng>(puzzle: &mut [Vec<char>], spec: &WordSpec, extra_copy: bool, options: &PuzzleOptions, rng: &mut R, attempts: &mut usize) -> Result<PlacedWord, FailureReason> {
+    // Extra copies don't cross other words, so one can't land on the cells of another.
+    if extra_copy {
+        place_spec(puzzle, spec, &PuzzleOptions { allow_overlap: false, ..options.clone() }, rng, attempts)
+    } else {
+        place_spec(puzzle, spec, options, rng, attempts)
+    }
+}
+
+/// Repeats each spec `count` times, along with whether each one is a copy after the first.
+fn spec_copies(specs: &[WordSpec]) -> (Vec<WordSpec>, Vec<bool>) {
+    specs.iter()
+        .flat_map(|spec| (0..spec.count).map(move |copy| (WordSpec { count: 1, ..spec.clone() }, copy > 0)))
+        .unzip()
+}
+
+/// The indexes of `specs` in the order they should be placed.
+fn placement_order(specs: &[WordSpec], options: &PuzzleOptions) -> Vec<usize> {
+    let mut order = (0..specs.len()).collect::<Vec<usize>>();
+    if options.sort_by_length {
+        order.sort_by_key(|index| Reverse(specs[*index].text.chars().count()));
+    }
+    order
+}
+
+fn place_spec<R: Rng>(puzzle: &mut [Vec<char>], spec: &WordSpec, options: &PuzzleOptions, rng: &mut R, attempts: &mut usize) -> Result<PlacedWord, FailureReason> {
+    if spec.allowed_directions.is_empty() {
+        add_single_word(puzzle, &spec.text, options, rng, attempts)
+    } else {
+        let word_options = PuzzleOptions { directions: spec.allowed_directions.clone(), ..options.clone() };
+        add_single_word(puzzle, &spec.text, &word_options, rng, attempts)
+    }
+}
+
+/// Splits the outcome of placing each spec into the placed words and the failures, both in the
+/// order the specs were given.
+fn split_outcomes(specs: &[WordSpec], mut outcomes: Vec<(usize, Result<PlacedWord, FailureReason>)>) -> (Vec<PlacedWord>, Vec<PlacementFailure>) {
+    outcomes.sort_by_key(|(index, _)| *index);
+
+    let mut words_added: Vec<PlacedWord> = Vec::new();
+    let mut words_not_added: Vec<PlacementFailure> = Vec::new();
+    for (index, outcome) in outcomes {
+        match outcome {
+            Ok(placed) => words_added.push(placed),
+            Err(reason) => words_not_added.push(PlacementFailure { word: specs[index].text.clone(), reason }),
+        }
+    }
+    (words_added, words_not_added)
+}
+
+fn add_single_word<R: Rng>(puzzle: &mut [Vec<char>], word: &str, options: &PuzzleOptions, rng: &mut R, attempts: &mut usize) -> Result<PlacedWord, FailureReason> {
+    let cased_word = prepare_word(puzzle, word, options)?;
+    add_word_to_puzzle(puzzle, &cased_word, options, rng, attempts).ok_or(FailureReason::NoSpaceFound)
+}
+
+/// Places `key_words` like a small crossword: the first goes anywhere, and every later one must
+/// cross a key word placed before it on a letter they share. Words that can't cross any of them
+/// are reported with `FailureReason::NoCrossingFound` and left out.
+pub fn add_crossing_words_to_puzzle<R: Rng>(puzzle: &mut [Vec<char>], key_words: &[&str], options: &PuzzleOptions, rng: &mut R) -> (Vec<PlacedWord>, Vec<PlacementFailure>) {
+    let mut placed: Vec<PlacedWord> = Vec::new();
+    let mut failures = Vec::new();
+    for word in key_words {
+        let outcome = if placed.is_empty() {
+            add_single_word(puzzle, word, options, rng, &mut 0)
+        } else {
+            prepare_word(puzzle, word, options)
+                .and_then(|cased_word| add_crossing_word(puzzle, &cased_word, &placed, options, rng).ok_or(FailureReason::NoCrossingFound))
+        };
+        match outcome {
+            Ok(placed_word) => placed.push(placed_word),
+            Err(reason) => failures.push(PlacementFailure { word: word.to_string(), reason }),
+        }
+    }
+    (placed, failures)
+}
+
+/// Places `word` at a random position where it shares at least one cell with `crossed`.
+fn add_crossing_word<R: Rng>(puzzle: &mut [Vec<char>], word: &str, crossed: &[PlacedWord], options: &PuzzleOptions, rng: &mut R) -> Option<PlacedWord> {
+    let crossed_cells = crossed.iter()
+        .flat_map(|placed_word| placed_word.coordinates_in(puzzle))
+        .collect::<HashSet<Coordinate>>();
+    let crossing_options = PuzzleOptions { allow_overlap: true, ..options.clone() };
+
+    let mut candidates = Vec::new();
+    for row in 0..puzzle.len() as i16 {
+        for column in 0..puzzle[0].len() as i16 {
+            for direction in options.allowed_directions() {
+                let candidate = PlacedWord { word: word.to_string(), start: Coordinate::new(row, column), direction: *direction };
+                if word_fits_with_options(puzzle, &candidate.start, direction, word, &crossing_options)
+                    && candidate.coordinates_in(puzzle).iter().any(|coordinate| crossed_cells.contains(coordinate)) {
+                    candidates.push(candidate);
+                }
+            }
+        }
+    }
+
+    let candidate = candidates.choose(rng)?.clone();
+    place_word(puzzle, &candidate.start, &candidate.direction, word);
+    Some(candidate)
+}
+
+#[cfg(test)]
+mod crossing_tests {
+    use super::*;
+
+    #[test]
+    fn it_crosses_two_words_on_a_shared_letter() {
+        for seed in 0..20 {
+            let mut puzzle = generate_empty_puzzle(10, 10);
+            let (placed, failures) = add_crossing_words_to_puzzle(&mut puzzle, &["planet", "orbit"], &PuzzleOptions::default(), &mut StdRng::seed_from_u64(seed));
+            assert!(failures.is_empty());
+            assert_eq!(placed.len(), 2);
+
+            let first = placed[0].coordinates_in(&puzzle).into_iter().collect::<HashSet<Coordinate>>();
+            let shared = placed[1].coordinates_in(&puzzle).into_iter()
+                .filter(|coordinate| first.contains(coordinate))
+                .collect::<Vec<Coordinate>>();
+            assert!(!shared.is_empty());
+            for coordinate in shared {
+                assert_eq!(puzzle[coordinate.row as usize][coordinate.column as usize], 'T');
+            }
+        }
+    }
+
+    #[test]
+    fn it_reports_words_that_cannot_cross() {
+        let mut puzzle = generate_empty_puzzle(6, 6);
+        let (placed, failures) = add_crossing_words_to_puzzle(&mut puzzle, &["cat", "dog"], &PuzzleOptions::default(), &mut StdRng::seed_from_u64(1));
+        assert_eq!(placed.len(), 1);
+        assert_eq!(failures, vec![PlacementFailure { word: "dog".to_string(), reason: FailureReason::NoCrossingFound }]);
+    }
+}
+
+/// Checks that `word` could be placed at all and returns it stripped and cased as it should
+/// appear in the puzzle.
+fn prepare_word(puzzle: &[Vec<char>], word: &str, options: &PuzzleOptions) -> Result<String, FailureReason> {
+    let word = strip_word(word);
+    if word.is_empty() {
+        return Err(FailureReason::EmptyWord);
+    }
+    if word_too_long(puzzle, &word, options) {
+        return Err(FailureReason::TooLongForGrid);
+    }
+
+    if options.preserve_case {
+        Ok(word)
+    } else {
+        Ok(options.charset.apply_case(&word))
+    }
+}
+
+/// Removes spaces and hyphens so phrases like "NEW YORK" or "T-REX" are hidden as one word.
+pub fn strip_word(word: &str) -> String {
+    word.chars()
+        .filter(|letter| !letter.is_whitespace() && *letter != '-')
+        .collect()
+}
+
+fn word_too_long(puzzle: &[Vec<char>], word: &str, options: &PuzzleOptions) -> bool {
+    word.chars().count() > longest_path(puzzle, options.allowed_directions(), options.wrap)
+}
+
+/// The most letters any word running in one of `directions` can have in `puzzle`. Across is the
+/// width and down is the height. A diagonal is never longer than the shorter side, unless it
+/// wraps, when it can run until it returns to the cell it started from. Checking it first means
+/// a word that can never fit fails straight away instead of after trying every position.
+fn longest_path(puzzle: &[Vec<char>], directions: &[Direction], wrap: bool) -> usize {
+    let width = puzzle.first().map(|row| row.len()).unwrap_or(0);
+    longest_path_within(width, puzzle.len(), directions, wrap)
+}
+
+/// Like `longest_path`, but for a grid of the given size.
+fn longest_path_within(width: usize, height: usize, directions: &[Direction], wrap: bool) -> usize {
+    if width == 0 {
+        return 0;
+    }
+    directions.iter()
+        .map(|direction| match direction {
+            Direction::Left | Direction::Right => width,
+            Direction::Up | Direction::Down => height,
+            _ if wrap => width / greatest_common_divisor(width, height) * height,
+            _ => width.min(height),
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+fn greatest_common_divisor(a: usize, b: usize) -> usize {
+    if b == 0 { a } else { greatest_common_divisor(b, a % b) }
+}
+
+#[cfg(test)]
+mod longest_path_tests {
+    use super::*;
+
+    #[test]
+    fn it_is_the_longer_side_of_the_grid() {
+        assert_eq!(longest_path(&generate_empty_puzzle(4, 7), &Direction::ALL, false), 7);
+        assert_eq!(longest_path(&generate_empty_puzzle(9, 3), &Direction::ALL, false), 9);
+        assert_eq!(longest_path(&generate_empty_puzzle(5, 5), &Direction::ALL, false), 5);
+        assert_eq!(longest_path(&[], &Direction::ALL, false), 0);
+    }
+
+    #[test]
+    fn it_only_counts_the_allowed_directions() {
+        let puzzle = generate_empty_puzzle(4, 7);
+        assert_eq!(longest_path(&puzzle, &[Direction::Right], false), 4);
+        assert_eq!(longest_path(&puzzle, &[Direction::Up], false), 7);
+        assert_eq!(longest_path(&puzzle, &[Direction::DownRight], false), 4);
+        assert_eq!(longest_path(&puzzle, &[Direction::DownRight], true), 28);
+        assert_eq!(longest_path(&generate_empty_puzzle(4, 6), &[Direction::UpLeft], true), 12);
+    }
+
+    #[test]
+    fn it_fails_a_word_too_long_for_the_allowed_directions_without_scanning() {
+        let across = PuzzleOptions { directions: vec![Direction::Right], ..PuzzleOptions::default() };
+        let (_, placed, failures, stats) = generate_puzzle_with_stats(4, 8, &["giraffe"], &across, 1);
+        assert!(placed.is_empty());
+        assert_eq!(failures[0].reason, FailureReason::TooLongForGrid);
+        assert_eq!(stats.attempts, 0);
+
+        let down = PuzzleOptions { directions: vec![Direction::Down], ..PuzzleOptions::default() };
+        let (_, placed, _) = generate_puzzle_with_options(4, 8, &["giraffe"], &down, 1);
+        assert_eq!(placed.len(), 1);
+    }
+
+    #[test]
+    fn it_places_a_word_that_only_fits_along_a_wrapping_diagonal() {
+        let diagonals = PuzzleOptions { directions: vec![Direction::DownRight], wrap: true, ..PuzzleOptions::default() };
+        let (puzzle, placed, failures) = generate_puzzle_with_options(3, 2, &["abcdef"], &diagonals, 1);
+        assert!(failures.is_empty());
+        assert!(find_words_wrapping(&puzzle, &["abcdef"]).contains(&placed[0]));
+
+        let across = PuzzleOptions { directions: vec![Direction::Right], ..diagonals };
+        let (_, placed, failures) = generate_puzzle_with_options(3, 2, &["abcdef"], &across, 1);
+        assert!(placed.is_empty());
+        assert_eq!(failures[0].reason, FailureReason::TooLongForGrid);
+    }
+
+    #[test]
+    fn it_fails_an_overlong_word_without_scanning() {
+        let (_, placed, failures, stats) = generate_puzzle_with_stats(5, 5, &["abcdefghijkl"], &PuzzleOptions::default(), 1);
+        assert!(placed.is_empty());
+        assert_eq!(failures, vec![PlacementFailure { word: "abcdefghijkl".to_string(), reason: FailureReason::TooLongForGrid }]);
+        assert_eq!(stats.attempts, 0);
+    }
+}
+
+/// Splits `words` into those that could be placed in a `width` by `height` grid and those that
+/// never could: empty words and words longer than the grid. Spaces and hyphens are ignored.
+///
+/// Words with other characters than letters, such as "R2D2", are also returned as warnings since
+/// they stand out against the letter filler. With `strict` they are rejected instead.
+pub fn validate_words(width: i16, height: i16, words: &[&str], strict: bool) -> (Vec<String>, Vec<String>, Vec<String>) {
+    let max_length = width.max(height).max(0) as usize;
+    let mut accepted = Vec::new();
+    let mut rejected = Vec::new();
+    let mut warnings = Vec::new();
+    for word in words.iter() {
+        let stripped = strip_word(word);
+        let only_letters = stripped.chars().all(char::is_alphabetic);
+        if stripped.is_empty() || stripped.chars().count() > max_length || (strict && !only_letters) {
+            rejected.push(word.to_string());
+            continue;
+        }
+        if !only_letters {
+            warnings.push(word.to_string());
+        }
+        accepted.push(word.to_string());
+    }
+    (accepted, rejected, warnings)
+}
+
+/// The smallest width or height that can fit every word: the length of the longest word.
+pub fn minimum_dimension(words: &[&str]) -> i16 {
+    words.iter()
+        .map(|word| strip_word(word).chars().count() as i16)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Suggests a square grid big enough that the letters of `words` take up roughly `density` of
+/// its cells, and never smaller than the longest word.
+pub fn suggest_dimensions(words: &[&str], density: f32) -> (i16, i16) {
+    let density = density.clamp(0.01, 1.0);
+    let total_letters = words.iter().map(|word| strip_word(word).chars().count()).sum::<usize>();
+    let side = ((total_letters as f32 / density).sqrt().ceil() as i16).max(minimum_dimension(words));
+    (side, side)
+}
+
+#[cfg(test)]
+mod validation_tests {
+    use super::*;
+
+    #[test]
+    fn it_accepts_words_that_can_fit() {
+        let (accepted, rejected, warnings) = validate_words(5, 3, &["cat", "horse", "t-rex"], false);
+        assert_eq!(accepted, vec!["cat", "horse", "t-rex"]);
+        assert!(rejected.is_empty());
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn it_rejects_words_that_can_never_fit() {
+        let (accepted, rejected, _) = validate_words(5, 3, &["", " - ", "giraffe", "new york", "dog"], false);
+        assert_eq!(accepted, vec!["dog"]);
+        assert_eq!(rejected, vec!["", " - ", "giraffe", "new york"]);
+    }
+
+    #[test]
+    fn it_flags_words_with_digits_or_punctuation() {
+        let (accepted, rejected, warnings) = validate_words(5, 5, &["R2D2", "cat", "wow!"], false);
+        assert_eq!(accepted, vec!["R2D2", "cat", "wow!"]);
+        assert!(rejected.is_empty());
+        assert_eq!(warnings, vec!["R2D2", "wow!"]);
+
+        let (accepted, rejected, warnings) = validate_words(5, 5, &["R2D2", "cat", "wow!"], true);
+        assert_eq!(accepted, vec!["cat"]);
+        assert_eq!(rejected, vec!["R2D2", "wow!"]);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn it_knows_the_minimum_dimension() {
+        assert_eq!(minimum_dimension(&["cat", "giraffe", "dog"]), 7);
+        assert_eq!(minimum_dimension(&[" ox "]), 2);
+        assert_eq!(minimum_dimension(&[]), 0);
+    }
+
+    #[test]
+    fn it_suggests_dimensions_for_a_density() {
+        assert_eq!(suggest_dimensions(&["cat", "dog"], 0.5), (4, 4));
+        assert_eq!(suggest_dimensions(&["apple", "banana", "cherry", "grape", "kiwi", "lemon", "mango", "orange"], 0.5), (10, 10));
+        assert_eq!(suggest_dimensions(&[], 0.5), (0, 0));
+    }
+
+    #[test]
+    fn it_never_suggests_dimensions_smaller_than_the_longest_word() {
+        assert_eq!(suggest_dimensions(&["elephant"], 0.5), (8, 8));
+    }
+
+    #[test]
+    fn it_only_counts_the_letters_of_phrases() {
+        assert_eq!(suggest_dimensions(&["ice cream", "t-rex"], 0.5), suggest_dimensions(&["icecream", "trex"], 0.5));
+        assert_eq!(suggest_dimensions(&["a b c d", "e - f g h"], 0.5), (4, 4));
+    }
+}
+
+pub fn remove_empty_spots<R: Rng>(puzzle: &mut [Vec<char>], charset: &CharsetOptions, rng: &mut R) {
+    for row in puzzle.iter_mut() {
+        for cell in row.iter_mut() {
+            if *cell == ' ' {
+                *cell = charset.random_character(rng);
+            }
         }
     }
+}
 
-    fn get_next_direction(&self) -> Direction {
-        Direction::from_number((*self as u8) + 1).unwrap_or_else(|| Direction::Up)
+/// Fills only `fraction` of the empty spots with random letters, leaving the rest blank for a
+/// sparser grid. `fraction` is clamped to 0.0..=1.0.
+pub fn remove_empty_spots_partial<R: Rng>(puzzle: &mut [Vec<char>], fraction: f32, rng: &mut R) {
+    let fraction = fraction.clamp(0.0, 1.0);
+    let empty_spots = empty_coordinates(puzzle);
+
+    let count = (empty_spots.len() as f32 * fraction).round() as usize;
+    for coordinate in empty_spots.choose_multiple(rng, count).copied().collect::<Vec<Coordinate>>() {
+        puzzle[coordinate.row as usize][coordinate.column as usize] = generate_random_character(rng);
     }
 }
 
+/// Every cell that is still empty, row by row. Holes from a mask are not included.
+pub fn empty_coordinates(puzzle: &[Vec<char>]) -> Vec<Coordinate> {
+    puzzle.iter()
+        .enumerate()
+        .flat_map(|(row, cells)| cells.iter()
+            .enumerate()
+            .filter(|(_, cell)| **cell == ' ')
+            .map(move |(column, _)| Coordinate::new(row as i16, column as i16)))
+        .collect()
+}
+
 #[cfg(test)]
-mod direction_tests {
+mod empty_coordinates_tests {
     use super::*;
 
     #[test]
-    fn it_knows_the_next_direction() {
-        assert_eq!(Direction::Up.get_next_direction(), Direction::Down);
-        assert_eq!(Direction::DownRight.get_next_direction(), Direction::Up);
+    fn it_lists_the_cells_no_word_passes_through() {
+        let mut puzzle = generate_empty_puzzle(3, 3);
+        place_word(&mut puzzle, &Coordinate::new(0, 0), &Direction::Right, "CAT");
+        place_word(&mut puzzle, &Coordinate::new(0, 0), &Direction::Down, "COW");
+        assert_eq!(empty_coordinates(&puzzle), vec![
+            Coordinate::new(1, 1), Coordinate::new(1, 2),
+            Coordinate::new(2, 1), Coordinate::new(2, 2),
+        ]);
     }
 
     #[test]
-    fn it_can_create_directions_from_numbers() {
-        assert_eq!(Direction::from_number(1), Some(Direction::Up));
-        assert_eq!(Direction::from_number(2), Some(Direction::Down));
-        assert_eq!(Direction::from_number(8), Some(Direction::DownRight));
-        assert_eq!(Direction::from_number(9), None);
+    fn it_returns_nothing_for_a_full_grid() {
+        assert!(empty_coordinates(&[vec!['A', 'B'], vec!['C', 'D']]).is_empty());
     }
 }
 
+/// Replaces every empty spot with `filler`, leaving the placed words untouched.
+pub fn fill_empty_spots(puzzle: &mut [Vec<char>], filler: char) {
+    for cell in puzzle.iter_mut().flat_map(|row| row.iter_mut()) {
+        if *cell == ' ' {
+            *cell = filler;
+        }
+    }
+}
 
+/// Fills empty spots with letters sampled according to how often they appear in `words`, so the
+/// filler blends in with the hidden words. Falls back to A-Z when `words` has no letters.
+pub fn remove_empty_spots_weighted<R: Rng>(puzzle: &mut [Vec<char>], words: &[&str], rng: &mut R) {
+    let frequencies = word_stats(words).letter_frequencies;
+    if frequencies.is_empty() {
+        remove_empty_spots(puzzle, &CharsetOptions::Uppercase, rng);
+        return;
+    }
 
-#[derive(Debug, PartialEq, Clone, Copy)]
-struct Coordinate {
-    row: i16,
-    column: i16,
+    let letters = frequencies.keys().copied().collect::<Vec<char>>();
+    let distribution = WeightedIndex::new(frequencies.values()).unwrap();
+    for row in puzzle.iter_mut() {
+        for cell in row.iter_mut() {
+            if *cell == ' ' {
+                *cell = letters[distribution.sample(rng)];
+            }
+        }
+    }
 }
 
+/// Places `word` and returns where it went, adding every position checked to `attempts`.
+fn add_word_to_puzzle<R: Rng>(puzzle: &mut [Vec<char>], word: &str, options: &PuzzleOptions, rng: &mut R, attempts: &mut usize) -> Option<PlacedWord> {
+    let (coordinate, direction) = match options.placement_strategy {
+        PlacementStrategy::Scan => scan_for_position(puzzle, word, options, rng, attempts)?,
+        PlacementStrategy::Random { attempts: max_attempts } => random_position(puzzle, word, options, max_attempts, rng, attempts)?,
+    };
+    place_word(puzzle, &coordinate, &direction, word);
+    Some(PlacedWord { word: word.to_string(), start: coordinate, direction })
+}
 
+/// Starts at a random position and direction, then tries every direction at each position in
+/// turn, scanning the grid row by row.
+fn scan_for_position<R: Rng>(puzzle: &[Vec<char>], word: &str, options: &PuzzleOptions, rng: &mut R, total_attempts: &mut usize) -> Option<(Coordinate, Direction)> {
+    let original_coordinate = Coordinate::new(rng.gen_range(0..puzzle.len()) as i16, rng.gen_range(0..puzzle[0].len()) as i16);
+    let mut coordinate = original_coordinate;
+    let directions = direction_cycle(options, rng);
+    let mut direction_index = 0;
+    let mut direction = directions[direction_index];
+    let mut attempts = 0;
 
-impl Coordinate {
-    fn new(row: i16, column: i16) -> Coordinate {
-        Coordinate {
-            row,
-            column,
+    *total_attempts += 1;
+    while !word_fits_with_options(puzzle, &coordinate, &direction, word, options) {
+        attempts += 1;
+        if options.max_attempts_per_word.is_some_and(|max_attempts| attempts >= max_attempts) {
+            return None;
+        }
+        direction_index = (direction_index + 1) % directions.len();
+        direction = directions[direction_index];
+        if direction_index == 0 {
+            coordinate = coordinate.get_next_coordinate_for_size(puzzle[0].len() as i16, puzzle.len() as i16);
+            if coordinate == original_coordinate {
+                return None;
+            }
         }
+        *total_attempts += 1;
     }
+    Some((coordinate, direction))
+}
 
-    fn get_next_coordinate(&self, direction: &Direction) -> Coordinate {
-        match direction {
-            Direction::Up => Coordinate::new(self.row - 1, self.column),
-            Direction::Down => Coordinate::new(self.row + 1, self.column),
-            Direction::Left => Coordinate::new(self.row, self.column - 1),
-            Direction::Right => Coordinate::new(self.row, self.column + 1),
-            Direction::UpLeft => Coordinate::new(self.row - 1, self.column - 1),
-            Direction::UpRight => Coordinate::new(self.row - 1, self.column + 1),
-            Direction::DownLeft => Coordinate::new(self.row + 1, self.column - 1),
-            Direction::DownRight => Coordinate::new(self.row + 1, self.column + 1),
+/// The order `scan_for_position` tries directions in at each position. It starts from a random
+/// allowed direction and follows the fixed direction order, or is a random permutation with
+/// `options.shuffle_directions`.
+fn direction_cycle<R: Rng>(options: &PuzzleOptions, rng: &mut R) -> Vec<Direction> {
+    let allowed = options.allowed_directions();
+    if options.shuffle_directions {
+        let mut directions = Vec::with_capacity(allowed.len());
+        for direction in allowed {
+            if !directions.contains(direction) {
+                directions.push(*direction);
+            }
         }
+        directions.shuffle(rng);
+        return directions;
     }
 
-    fn get_next_coordinate_for_size(&self, width: i16, height: i16) -> Coordinate {
-        if self.column + 1 < width {
-            Coordinate::new(self.row, self.column + 1)
-        } else if self.row + 1 < height {
-            Coordinate::new(self.row + 1, 0)
-        } else {
-            Coordinate::new(0, 0)
+    let first = choose_direction(options, rng);
+    let mut directions = vec![first];
+    let mut direction = first.get_next_allowed_direction(allowed);
+    while direction != first {
+        directions.push(direction);
+        direction = direction.get_next_allowed_direction(allowed);
+    }
+    directions
+}
+
+/// Picks one of the allowed directions at random, following `options.direction_weights` if set.
+/// Falls back to picking evenly when the allowed directions all have a weight of zero.
+fn choose_direction<R: Rng>(options: &PuzzleOptions, rng: &mut R) -> Direction {
+    let allowed = options.allowed_directions();
+    if let Some(weights) = options.direction_weights {
+        let allowed_weights = allowed.iter()
+            .map(|direction| weights[Direction::ALL.iter().position(|candidate| candidate == direction).unwrap()].max(0.0))
+            .collect::<Vec<f32>>();
+        if let Ok(distribution) = WeightedIndex::new(&allowed_weights) {
+            return allowed[distribution.sample(rng)];
         }
     }
+    *allowed.choose(rng).unwrap()
+}
+
+#[cfg(test)]
+mod direction_weight_tests {
+    use super::*;
+
+    #[test]
+    fn it_follows_the_direction_weights() {
+        let mut weights = [0.0; 8];
+        weights[3] = 6.0;
+        weights[1] = 2.0;
+        weights[7] = 2.0;
+        let options = PuzzleOptions { direction_weights: Some(weights), ..PuzzleOptions::default() };
+        let words = (0..150).map(|index| format!("w{}", index)).collect::<Vec<String>>();
+        let words = words.iter().map(String::as_str).collect::<Vec<&str>>();
+
+        let (_, placed, failures) = generate_puzzle_with_options(50, 50, &words, &options, 3);
+        assert!(failures.is_empty());
+        let histogram = direction_histogram(&placed);
+        let share = |direction| *histogram.get(&direction).unwrap_or(&0) as f32 / placed.len() as f32;
+        assert!((share(Direction::Right) - 0.6).abs() < 0.1);
+        assert!((share(Direction::Down) - 0.2).abs() < 0.1);
+        assert!((share(Direction::DownRight) - 0.2).abs() < 0.1);
+    }
 
-    fn valid(&self, width: i16, height: i16) -> bool {
-        self.row >= 0 && self.row < height && self.column >= 0 && self.column < width
+    #[test]
+    fn it_ignores_weights_that_rule_out_every_allowed_direction() {
+        let mut weights = [0.0; 8];
+        weights[0] = 1.0;
+        let options = PuzzleOptions { directions: vec![Direction::Right, Direction::Left], direction_weights: Some(weights), ..PuzzleOptions::default() };
+        let mut rng = StdRng::seed_from_u64(1);
+        for _ in 0..20 {
+            assert!(options.directions.contains(&choose_direction(&options, &mut rng)));
+        }
     }
 }
 
 #[cfg(test)]
-mod coordinate_tests {
+mod direction_cycle_tests {
     use super::*;
 
     #[test]
-    fn it_can_get_the_next_coordinate() {
-        assert_eq!(Coordinate::new(1, 3), Coordinate::new(1, 2).get_next_coordinate(&Direction::Right));
-        assert_eq!(Coordinate::new(3, 3), Coordinate::new(2, 2).get_next_coordinate(&Direction::DownRight));
+    fn it_follows_the_fixed_order_by_default() {
+        let options = PuzzleOptions { directions: vec![Direction::Right, Direction::Up, Direction::DownRight], ..PuzzleOptions::default() };
+        let directions = direction_cycle(&options, &mut StdRng::seed_from_u64(2));
+        let start = directions.iter().position(|direction| *direction == Direction::Up).unwrap();
+        let rotated = directions[start..].iter().chain(&directions[..start]).copied().collect::<Vec<Direction>>();
+        assert_eq!(rotated, vec![Direction::Up, Direction::Right, Direction::DownRight]);
     }
 
     #[test]
-    fn it_can_get_the_next_coordinate_for_the_size() {
-        assert_eq!(Coordinate::new(0, 1), Coordinate::new(0, 0).get_next_coordinate_for_size(2, 2));
-        assert_eq!(Coordinate::new(1, 0), Coordinate::new(0, 1).get_next_coordinate_for_size(2, 2));
-        assert_eq!(Coordinate::new(1, 1), Coordinate::new(1, 0).get_next_coordinate_for_size(2, 2));
-        assert_eq!(Coordinate::new(0, 0), Coordinate::new(1, 1).get_next_coordinate_for_size(2, 2));
+    fn it_shuffles_every_allowed_direction_once() {
+        let options = PuzzleOptions { shuffle_directions: true, ..PuzzleOptions::default() };
+        let mut rng = StdRng::seed_from_u64(2);
+        let cycles = (0..20).map(|_| direction_cycle(&options, &mut rng)).collect::<Vec<Vec<Direction>>>();
+        for cycle in cycles.iter() {
+            assert_eq!(cycle.len(), Direction::ALL.len());
+            assert!(Direction::ALL.iter().all(|direction| cycle.contains(direction)));
+        }
+        assert!(cycles.iter().any(|cycle| cycle != &cycles[0]));
     }
 
     #[test]
-    fn it_knows_if_a_coordinate_is_valid() {
-        assert!(Coordinate::new(0, 0).valid(2, 2));
-        assert!(Coordinate::new(1, 1).valid(2, 2));
-        assert!(!Coordinate::new(2, 2).valid(2, 2));
-        assert!(!Coordinate::new(-1, 0).valid(2, 2));
-        assert!(!Coordinate::new(0, -1).valid(2, 2));
+    fn it_varies_the_directions_of_placed_words() {
+        let options = PuzzleOptions { shuffle_directions: true, ..PuzzleOptions::default() };
+        let words = ["apple", "banana", "cherry", "grape", "lemon", "mango", "melon", "peach", "plum", "kiwi"];
+        let (_, placed, failures) = generate_puzzle_with_options(12, 12, &words, &options, 7);
+        assert!(failures.is_empty());
+        let directions = placed.iter().map(|placed_word| placed_word.direction as u8).collect::<HashSet<u8>>();
+        assert!(directions.len() > 2);
     }
+}
 
+/// Tries up to `attempts` random positions and directions.
+fn random_position<R: Rng>(puzzle: &[Vec<char>], word: &str, options: &PuzzleOptions, attempts: usize, rng: &mut R, total_attempts: &mut usize) -> Option<(Coordinate, Direction)> {
+    for _ in 0..attempts {
+        let coordinate = Coordinate::new(rng.gen_range(0..puzzle.len()) as i16, rng.gen_range(0..puzzle[0].len()) as i16);
+        let direction = choose_direction(options, rng);
+        *total_attempts += 1;
+        if word_fits_with_options(puzzle, &coordinate, &direction, word, options) {
+            return Some((coordinate, direction));
+        }
+    }
+    None
 }
 
-pub fn generate_empty_puzzle(width: i16, height: i16) -> Vec<Vec<char>> {
-    let mut puzzle: Vec<Vec<char>> = Vec::with_capacity(height as usize);
-    for _ in 0..height {
-        let row = vec![' '; width as usize];
-        puzzle.push(row);
+/// Writes `word` into `puzzle` starting at `coordinate` if it fits there, and returns whether it
+/// did. The word may cross cells that already hold the same letter. Empty cells are not filled.
+pub fn try_place_word(puzzle: &mut [Vec<char>], word: &str, coordinate: Coordinate, direction: Direction) -> bool {
+    if word.is_empty() || !word_fits(puzzle, &coordinate, &direction, word, true) {
+        return false;
     }
-    puzzle
+    place_word(puzzle, &coordinate, &direction, word);
+    true
 }
 
-pub fn add_words_to_puzzle<'a>(puzzle: &mut Vec<Vec<char>>, words: &'a Vec<&'a str>) -> Vec<&'a str> {
-    let mut words_not_added: Vec<&str> = Vec::new();
-    for word in words {
-        if !add_word_to_puzzle(puzzle, &word.to_uppercase()) {
-            words_not_added.push(word);
+#[cfg(test)]
+mod try_place_word_tests {
+    use super::*;
+
+    #[test]
+    fn it_places_a_word_that_fits() {
+        let mut puzzle = generate_empty_puzzle(4, 3);
+        assert!(try_place_word(&mut puzzle, "CAT", Coordinate::new(0, 0), Direction::Down));
+        assert!(try_place_word(&mut puzzle, "TOE", Coordinate::new(2, 0), Direction::Right));
+        assert_eq!(puzzle, vec![
+            vec!['C', ' ', ' ', ' '],
+            vec!['A', ' ', ' ', ' '],
+            vec!['T', 'O', 'E', ' '],
+        ]);
+    }
 
-        }
+    #[test]
+    fn it_leaves_the_grid_alone_when_a_word_does_not_fit() {
+        let mut puzzle = generate_empty_puzzle(4, 3);
+        assert!(try_place_word(&mut puzzle, "CAT", Coordinate::new(0, 0), Direction::Right));
+        let before = puzzle.clone();
+        assert!(!try_place_word(&mut puzzle, "DOG", Coordinate::new(0, 0), Direction::Down));
+        assert!(!try_place_word(&mut puzzle, "BIRD", Coordinate::new(1, 1), Direction::Right));
+        assert_eq!(puzzle, before);
     }
-    words_not_added
 }
 
-pub fn remove_empty_spots(puzzle: &mut Vec<Vec<char>>) {
-    for row in puzzle.iter_mut() {
-        for cell in row.iter_mut() {
-            if *cell == ' ' {
-                *cell = generate_random_character();
-            }
+/// Clears the cells of `removed` back to blanks, except those it shares with another word in
+/// `placed`, so crossing words stay intact. `placed` may include `removed` itself.
+pub fn remove_word(puzzle: &mut [Vec<char>], removed: &PlacedWord, placed: &[PlacedWord]) {
+    let kept_cells = placed.iter()
+        .filter(|placed_word| *placed_word != removed)
+        .flat_map(|placed_word| placed_word.coordinates_in(puzzle))
+        .collect::<HashSet<Coordinate>>();
+    for coordinate in removed.coordinates_in(puzzle) {
+        if !kept_cells.contains(&coordinate) && cell_at(puzzle, &coordinate).is_some() {
+            puzzle[coordinate.row as usize][coordinate.column as usize] = ' ';
         }
     }
 }
 
-fn add_word_to_puzzle(puzzle: &mut Vec<Vec<char>>, word: &str) -> bool {
-    let mut rng = thread_rng();
-    let original_coordinate = Coordinate::new(rng.gen_range(0..puzzle.len()) as i16, rng.gen_range(0..puzzle[0].len()) as i16);
-    let mut coordinate = original_coordinate;
-    let original_direction = Direction::from_number(rng.gen_range(1..=8)).unwrap();
-    let mut direction = original_direction;
+#[cfg(test)]
+mod remove_word_tests {
+    use super::*;
 
-    while !word_fits(puzzle, &coordinate, &direction, word.len()) {
-        direction = direction.get_next_direction();
-        if direction == original_direction {
-            coordinate = coordinate.get_next_coordinate_for_size(puzzle[0].len() as i16, puzzle.len() as i16);
-            if coordinate == original_coordinate {
-                return false;
-            }
+    #[test]
+    fn it_keeps_the_cell_shared_with_a_crossing_word() {
+        let mut puzzle = generate_empty_puzzle(3, 3);
+        let cat = PlacedWord { word: "CAT".to_string(), start: Coordinate::new(0, 0), direction: Direction::Down };
+        let toe = PlacedWord { word: "TOE".to_string(), start: Coordinate::new(2, 0), direction: Direction::Right };
+        assert!(try_place_word(&mut puzzle, &cat.word, cat.start, cat.direction));
+        assert!(try_place_word(&mut puzzle, &toe.word, toe.start, toe.direction));
+
+        remove_word(&mut puzzle, &cat, &[cat.clone(), toe.clone()]);
+        assert_eq!(puzzle, vec![
+            vec![' ', ' ', ' '],
+            vec![' ', ' ', ' '],
+            vec!['T', 'O', 'E'],
+        ]);
+    }
+
+    #[test]
+    fn it_clears_a_word_that_crosses_nothing() {
+        let mut puzzle = generate_empty_puzzle(3, 1);
+        let cat = PlacedWord { word: "CAT".to_string(), start: Coordinate::new(0, 0), direction: Direction::Right };
+        assert!(try_place_word(&mut puzzle, &cat.word, cat.start, cat.direction));
+        remove_word(&mut puzzle, &cat, &[]);
+        assert_eq!(puzzle, generate_empty_puzzle(3, 1));
+    }
+}
+
+fn place_word(puzzle: &mut [Vec<char>], coordinate: &Coordinate, direction: &Direction, word: &str) {
+    let placed_word = PlacedWord { word: word.to_string(), start: *coordinate, direction: *direction };
+    for (letter, coordinate) in word.chars().zip(placed_word.coordinates_in(puzzle)) {
+        puzzle[coordinate.row as usize][coordinate.column as usize] = letter;
+    }
+}
+
+fn word_fits_with_options(puzzle: &[Vec<char>], coordinate: &Coordinate, direction: &Direction, word: &str, options: &PuzzleOptions) -> bool {
+    if options.border_margin > 0 && !inside_margin(puzzle, coordinate, direction, word, options) {
+        return false;
+    }
+    if options.wrap {
+        word_fits_wrapping(puzzle, coordinate, direction, word, options.allow_overlap)
+    } else {
+        word_fits(puzzle, coordinate, direction, word, options.allow_overlap)
+    }
+}
+
+fn word_fits(puzzle: &[Vec<char>], coordinate: &Coordinate, direction: &Direction, word: &str, allow_overlap: bool) -> bool {
+    let mut coordinate = *coordinate;
+    for letter in word.chars() {
+        if !cell_at(puzzle, &coordinate).is_some_and(|cell| cell_accepts(cell, letter, allow_overlap)) {
+            return false;
         }
+        coordinate = coordinate.get_next_coordinate(direction);
     }
-    place_word(puzzle, &coordinate, &direction, word);
     true
 }
 
-fn place_word(puzzle: &mut Vec<Vec<char>>, coordinate: &Coordinate, direction: &Direction, word: &str) {
-    if !word.is_empty() {
-        puzzle[coordinate.row as usize][coordinate.column as usize] = word.chars().next().unwrap();
-        let next_coordinate = coordinate.get_next_coordinate(direction);
-        place_word(puzzle, &next_coordinate, direction, &word[1..]);
+fn word_fits_wrapping(puzzle: &[Vec<char>], coordinate: &Coordinate, direction: &Direction, word: &str, allow_overlap: bool) -> bool {
+    let Some(path) = word_path(puzzle, coordinate, direction, word.chars().count(), true) else {
+        return false;
+    };
+    word.chars().zip(path)
+        .all(|(letter, coordinate)| cell_at(puzzle, &coordinate).is_some_and(|cell| cell_accepts(cell, letter, allow_overlap)))
+}
+
+/// The cells a word of `length` letters passes through from `coordinate`, wrapping around the
+/// edges if `wrap` is set. Returns `None` if a wrapped word would pass through a cell twice.
+fn word_path(puzzle: &[Vec<char>], coordinate: &Coordinate, direction: &Direction, length: usize, wrap: bool) -> Option<Vec<Coordinate>> {
+    let placed_word = PlacedWord { word: "?".repeat(length), start: *coordinate, direction: *direction };
+    if !wrap {
+        return Some(placed_word.coordinates());
+    }
+    let path = placed_word.coordinates_in(puzzle);
+    if path.iter().collect::<HashSet<&Coordinate>>().len() < path.len() {
+        return None;
+    }
+    Some(path)
+}
+
+/// Whether every letter of `word` would be at least `options.border_margin` cells from the edges.
+fn inside_margin(puzzle: &[Vec<char>], coordinate: &Coordinate, direction: &Direction, word: &str, options: &PuzzleOptions) -> bool {
+    let height = puzzle.len() as i16;
+    let width = puzzle.first().map(|row| row.len()).unwrap_or(0) as i16;
+    let margin = options.border_margin;
+    word_path(puzzle, coordinate, direction, word.chars().count(), options.wrap).is_some_and(|path| {
+        path.iter().all(|cell| (margin..height - margin).contains(&cell.row) && (margin..width - margin).contains(&cell.column))
+    })
+}
+
+#[cfg(test)]
+mod border_margin_tests {
+    use super::*;
+
+    #[test]
+    fn it_keeps_words_off_the_border() {
+        let options = PuzzleOptions { border_margin: 1, ..PuzzleOptions::default() };
+        for seed in 0..10 {
+            let (puzzle, placed, _) = generate_puzzle_with_options(5, 5, &["cat", "dog"], &options, seed);
+            assert!(!placed.is_empty());
+            for coordinate in placed.iter().flat_map(|placed_word| placed_word.coordinates()) {
+                assert!((1..4).contains(&coordinate.row) && (1..4).contains(&coordinate.column), "{:?} is on the border", coordinate);
+            }
+            assert!(puzzle.iter().flatten().all(|cell| cell.is_ascii_uppercase()));
+        }
+    }
+
+    #[test]
+    fn it_fails_words_too_long_for_the_inner_grid() {
+        let options = PuzzleOptions { border_margin: 1, ..PuzzleOptions::default() };
+        let (_, placed, failures) = generate_puzzle_with_options(5, 5, &["horse"], &options, 1);
+        assert!(placed.is_empty());
+        assert_eq!(failures[0].reason, FailureReason::NoSpaceFound);
     }
 }
 
-fn word_fits(puzzle: &Vec<Vec<char>>, coordinate: &Coordinate, direction: &Direction, word_length: usize) -> bool {
-    return word_length <= 0 ||
-        (coordinate.valid(puzzle[0].len() as i16, puzzle.len() as i16) &&
-            puzzle[coordinate.row as usize][coordinate.column as usize] == ' ' &&
-            word_fits(puzzle, &coordinate.get_next_coordinate(direction), direction, word_length - 1));
+fn cell_accepts(cell: char, letter: char, allow_overlap: bool) -> bool {
+    cell != HOLE && (cell == ' ' || (allow_overlap && cell == letter))
 }
 
-fn generate_random_character() -> char {
-    let mut rng = thread_rng();
-    let random_number = rng.gen_range(0..26);
-    let random_character = (random_number + 65) as u8 as char;
-    random_character
+fn generate_random_character<R: Rng>(rng: &mut R) -> char {
+    (rng.gen_range(0..26) + 65) as u8 as char
 }
 
 #[cfg(test)]
@@ -227,23 +3247,317 @@ mod puzzle_tests {
     #[test]
     fn it_knows_if_a_word_fits() {
         let puzzle = generate_empty_puzzle(10, 10);
-        assert!(word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::Right, 5));
-        assert!(word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::Right, 10));
-        assert!(!word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::Right, 11));
+        assert!(word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::Right, "AAAAA", false));
+        assert!(word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::Right, "AAAAAAAAAA", false));
+        assert!(!word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::Right, "AAAAAAAAAAA", false));
+
+        assert!(word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::DownRight, "A", false));
+        assert!(word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::DownRight, "AAAAAAAAAA", false));
+        assert!(word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::Down, "AAAAAAAAAA", false));
+
+        assert!(word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::DownLeft, "A", false));
+        assert!(!word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::DownLeft, "AA", false));
+
+
+        assert!(word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::Left, "A", false));
+        assert!(!word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::Left, "AA", false));
+
+        assert!(!word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::UpLeft, "AA", false));
+        assert!(!word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::Up, "AA", false));
+        assert!(!word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::UpRight, "AA", false));
+    }
+
+    #[test]
+    fn it_only_fits_over_matching_letters_when_overlap_is_allowed() {
+        let mut puzzle = generate_empty_puzzle(5, 5);
+        place_word(&mut puzzle, &Coordinate::new(0, 0), &Direction::Right, "CAT");
+        assert!(!word_fits(&puzzle, &Coordinate::new(0, 1), &Direction::Down, "ANT", false));
+        assert!(word_fits(&puzzle, &Coordinate::new(0, 1), &Direction::Down, "ANT", true));
+        assert!(!word_fits(&puzzle, &Coordinate::new(0, 1), &Direction::Down, "BAT", true));
+    }
+
+    #[test]
+    fn it_can_place_words_crossing_at_a_shared_letter() {
+        let mut puzzle = generate_empty_puzzle(5, 5);
+        place_word(&mut puzzle, &Coordinate::new(2, 0), &Direction::Right, "HOUSE");
+        assert!(word_fits(&puzzle, &Coordinate::new(1, 2), &Direction::Down, "MUD", true));
+        place_word(&mut puzzle, &Coordinate::new(1, 2), &Direction::Down, "MUD");
+
+        assert_eq!(puzzle[2].iter().collect::<String>(), "HOUSE");
+        let column = puzzle.iter().skip(1).take(3).map(|row| row[2]).collect::<String>();
+        assert_eq!(column, "MUD");
+    }
+
+    #[test]
+    fn it_overlaps_words_to_fit_them_in_a_small_grid() {
+        let words = vec!["abc", "bc"];
+        let options = PuzzleOptions { directions: vec![Direction::Right], ..PuzzleOptions::default() };
+        for seed in 0..20 {
+            let (puzzle, placed, failures) = generate_puzzle_with_options(3, 1, &words, &options, seed);
+            assert!(failures.is_empty());
+            for placed_word in placed.iter() {
+                let letters = placed_word.coordinates().iter()
+                    .map(|coordinate| puzzle[coordinate.row as usize][coordinate.column as usize])
+                    .collect::<String>();
+                assert_eq!(letters, placed_word.word);
+            }
+        }
+    }
+
+    #[test]
+    fn it_generates_the_same_puzzle_for_the_same_seed() {
+        let words = vec!["apple", "banana", "cherry"];
+        let first = generate_puzzle_seeded(10, 10, &words, 42);
+        let second = generate_puzzle_seeded(10, 10, &words, 42);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn it_generates_different_puzzles_for_different_seeds() {
+        let words = vec!["apple", "banana", "cherry"];
+        let (first, _) = generate_puzzle_seeded(10, 10, &words, 1);
+        let (second, _) = generate_puzzle_seeded(10, 10, &words, 2);
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn it_returns_where_each_word_was_placed() {
+        let words = vec!["apple", "banana", "cherry"];
+        let (puzzle, placed, not_placed) = generate_puzzle_with_solution_seeded(10, 10, &words, 7);
+        assert!(not_placed.is_empty());
+        assert_eq!(placed.len(), 3);
+
+        for placed_word in placed.iter() {
+            let letters = placed_word.coordinates().iter()
+                .map(|coordinate| puzzle[coordinate.row as usize][coordinate.column as usize])
+                .collect::<String>();
+            assert_eq!(letters, placed_word.word);
+        }
+    }
+
+    #[test]
+    fn it_only_places_words_in_allowed_directions() {
+        let words = vec!["apple", "banana", "cherry", "date", "elderberry", "fig"];
+        let options = PuzzleOptions { directions: vec![Direction::Right, Direction::Down], ..PuzzleOptions::default() };
+        for seed in 0..20 {
+            let (_, placed, _) = generate_puzzle_with_options(10, 10, &words, &options, seed);
+            assert!(placed.iter().all(|placed_word| options.directions.contains(&placed_word.direction)));
+        }
+    }
+
+    #[test]
+    fn it_allows_every_direction_when_none_are_given() {
+        let options = PuzzleOptions { directions: vec![], ..PuzzleOptions::default() };
+        assert_eq!(options.allowed_directions(), &Direction::ALL);
+    }
+
+    #[test]
+    fn it_reports_why_words_could_not_be_placed() {
+        let words = vec!["", "fantastic", "dog", "cat"];
+        let options = PuzzleOptions { directions: vec![Direction::Right], allow_overlap: false, ..PuzzleOptions::default() };
+        let (_, placed, failures) = generate_puzzle_with_options(3, 1, &words, &options, 3);
+
+        assert_eq!(placed.len(), 1);
+        assert_eq!(failures, vec![
+            PlacementFailure { word: "".to_string(), reason: FailureReason::EmptyWord },
+            PlacementFailure { word: "fantastic".to_string(), reason: FailureReason::TooLongForGrid },
+            PlacementFailure { word: "cat".to_string(), reason: FailureReason::NoSpaceFound },
+        ]);
+    }
+
+    #[test]
+    fn it_maps_failures_back_to_the_original_words() {
+        let words = vec!["dog", "cat", "dog"];
+        let failures = vec![PlacementFailure { word: "dog".to_string(), reason: FailureReason::NoSpaceFound }];
+        assert_eq!(failed_words(&words, &failures), vec!["dog"]);
+    }
+
+    #[test]
+    fn it_gives_up_on_a_word_once_its_attempts_are_used() {
+        let words = ('a'..='z').flat_map(|first| ('a'..='b').map(move |second| format!("{}{}", first, second))).collect::<Vec<String>>();
+        let words = words.iter().map(String::as_str).take(50).collect::<Vec<&str>>();
+        let options = PuzzleOptions { allow_overlap: false, max_attempts_per_word: Some(3), ..PuzzleOptions::default() };
+        let (_, placed, failures) = generate_puzzle_with_options(2, 2, &words, &options, 11);
+
+        assert!(placed.len() <= 2);
+        assert_eq!(placed.len() + failures.len(), 50);
+        assert!(failures.iter().all(|failure| failure.reason == FailureReason::NoSpaceFound));
+    }
+
+    #[test]
+    fn it_fills_empty_spots_with_letters_from_the_words() {
+        let mut puzzle = generate_empty_puzzle(10, 10);
+        remove_empty_spots_weighted(&mut puzzle, &["aab", "ba"], &mut StdRng::seed_from_u64(5));
+
+        let filler = puzzle.iter().flatten().collect::<Vec<&char>>();
+        assert!(filler.iter().all(|letter| **letter == 'A' || **letter == 'B'));
+        assert!(filler.iter().any(|letter| **letter == 'A'));
+        assert!(filler.iter().any(|letter| **letter == 'B'));
+    }
+
+    #[test]
+    fn it_falls_back_to_any_letter_without_words() {
+        let mut puzzle = generate_empty_puzzle(10, 10);
+        remove_empty_spots_weighted(&mut puzzle, &[], &mut StdRng::seed_from_u64(5));
+        assert!(puzzle.iter().flatten().all(|letter| letter.is_ascii_uppercase()));
+    }
+
+    #[test]
+    fn it_places_words_with_accented_letters() {
+        let mut puzzle = generate_empty_puzzle(4, 4);
+        assert!(word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::Right, "NIÑO", false));
+        assert!(!word_fits(&puzzle, &Coordinate::new(0, 1), &Direction::Right, "NIÑO", false));
+        place_word(&mut puzzle, &Coordinate::new(0, 0), &Direction::Right, "NIÑO");
+        place_word(&mut puzzle, &Coordinate::new(3, 3), &Direction::Left, "CAFÉ");
+
+        assert_eq!(puzzle[0], vec!['N', 'I', 'Ñ', 'O']);
+        assert_eq!(puzzle[3], vec!['É', 'F', 'A', 'C']);
+    }
+
+    #[test]
+    fn it_generates_puzzles_with_accented_words() {
+        let words = vec!["niño", "café", "über"];
+        let (puzzle, placed, failures) = generate_puzzle_with_options(4, 4, &words, &PuzzleOptions::default(), 13);
+        assert!(failures.is_empty());
+        assert_eq!(placed.iter().map(|placed_word| placed_word.word.as_str()).collect::<Vec<&str>>(), vec!["NIÑO", "CAFÉ", "ÜBER"]);
+
+        for placed_word in placed.iter() {
+            let letters = placed_word.coordinates().iter()
+                .map(|coordinate| puzzle[coordinate.row as usize][coordinate.column as usize])
+                .collect::<String>();
+            assert_eq!(letters, placed_word.word);
+        }
+    }
+
+    #[test]
+    fn it_uses_the_charset_for_words_and_filler() {
+        let words = vec!["Cat", "Dog"];
+        let options = PuzzleOptions { charset: CharsetOptions::Lowercase, ..PuzzleOptions::default() };
+        let (puzzle, placed, _) = generate_puzzle_with_options(5, 5, &words, &options, 17);
+
+        assert!(puzzle.iter().flatten().all(|letter| letter.is_ascii_lowercase()));
+        assert_eq!(placed.iter().map(|placed_word| placed_word.word.as_str()).collect::<Vec<&str>>(), vec!["cat", "dog"]);
+    }
+
+    #[test]
+    fn it_preserves_the_case_of_words() {
+        let words = vec!["cat", "dog"];
+        let options = PuzzleOptions { preserve_case: true, ..PuzzleOptions::default() };
+        let (puzzle, placed, _) = generate_puzzle_with_options(5, 5, &words, &options, 19);
+
+        assert_eq!(placed.iter().map(|placed_word| placed_word.word.as_str()).collect::<Vec<&str>>(), vec!["cat", "dog"]);
+        assert!(puzzle.iter().flatten().all(|letter| letter.is_ascii_lowercase()));
+    }
 
-        assert!(word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::DownRight, 1));
-        assert!(word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::DownRight, 10));
-        assert!(word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::Down, 10));
+    #[test]
+    fn it_mixes_filler_case_when_words_mix_case() {
+        let words = vec!["Paris", "Rome"];
+        let options = PuzzleOptions { preserve_case: true, ..PuzzleOptions::default() };
+        let (_, placed, _) = generate_puzzle_with_options(6, 6, &words, &options, 19);
+        assert!(matches!(filler_charset(&options, &placed), CharsetOptions::Custom(_)));
+    }
+
+    #[test]
+    fn it_places_more_words_when_sorting_by_length() {
+        let words = vec!["ab", "cd", "efghij"];
+        let placed_count = |sort_by_length: bool| -> usize {
+            let options = PuzzleOptions { directions: vec![Direction::Right], allow_overlap: false, sort_by_length, ..PuzzleOptions::default() };
+            (0..50).map(|seed| generate_puzzle_with_options(6, 2, &words, &options, seed).1.len()).sum()
+        };
+
+        assert_eq!(placed_count(true), 150);
+        assert!(placed_count(false) < placed_count(true));
+    }
+
+    #[test]
+    fn it_backtracks_to_fit_words_greedy_placement_cannot() {
+        let words = vec!["ab", "cd", "ghij", "klmn", "opqr"];
+        let options = PuzzleOptions { directions: vec![Direction::Right], allow_overlap: false, ..PuzzleOptions::default() };
+
+        let greedy_failures: usize = (0..50)
+            .map(|seed| generate_puzzle_with_options(4, 4, &words, &options, seed).2.len())
+            .sum();
+        assert!(greedy_failures > 0);
+
+        for seed in 0..50 {
+            let (puzzle, placed, failures) = generate_puzzle_backtracking(4, 4, &words, &options, seed);
+            assert!(failures.is_empty());
+            assert_eq!(placed.iter().map(|placed_word| placed_word.word.as_str()).collect::<Vec<&str>>(), vec!["AB", "CD", "GHIJ", "KLMN", "OPQR"]);
+            for placed_word in placed.iter() {
+                let letters = placed_word.coordinates().iter()
+                    .map(|coordinate| puzzle[coordinate.row as usize][coordinate.column as usize])
+                    .collect::<String>();
+                assert_eq!(letters, placed_word.word);
+            }
+        }
+    }
+
+    #[test]
+    fn it_only_places_duplicate_words_once() {
+        let words = vec!["cat", "CAT", "dog"];
+        let (_, placed, failures) = generate_puzzle_with_options(5, 5, &words, &PuzzleOptions::default(), 23);
+        assert_eq!(placed.iter().map(|placed_word| placed_word.word.as_str()).collect::<Vec<&str>>(), vec!["CAT", "DOG"]);
+        assert!(failures.is_empty());
+    }
 
-        assert!(word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::DownLeft, 1));
-        assert!(!word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::DownLeft, 2));
+    #[test]
+    fn it_generates_multiple_distinct_puzzles() {
+        let words = vec!["apple", "banana", "cherry"];
+        let puzzles = generate_puzzles(3, 10, 10, &words, &PuzzleOptions::default(), 100);
+        assert_eq!(puzzles.len(), 3);
+        assert_ne!(puzzles[0], puzzles[1]);
+        assert_ne!(puzzles[1], puzzles[2]);
+        assert_eq!(puzzles[1], generate_puzzles(1, 10, 10, &words, &PuzzleOptions::default(), 101)[0]);
+    }
+
+    #[test]
+    fn it_generates_every_puzzle_with_the_options() {
+        let options = PuzzleOptions { directions: vec![Direction::Down], ..PuzzleOptions::default() };
+        let puzzles = generate_puzzles(3, 10, 10, &["apple", "banana", "cherry"], &options, 100);
+        for (_, placed) in puzzles {
+            assert!(placed.iter().all(|placed_word| placed_word.direction == Direction::Down));
+        }
+    }
+
+    #[test]
+    fn it_does_not_panic_on_non_positive_dimensions() {
+        let words = vec!["cat", "dog"];
+        for (width, height) in [(0, 5), (5, 0), (0, 0), (-3, 5)] {
+            let (puzzle, not_added) = generate_puzzle(width, height, &words);
+            assert!(puzzle.is_empty());
+            assert_eq!(not_added, vec!["cat", "dog"]);
 
+            let (puzzle, placed, _) = generate_puzzle_backtracking(width, height, &words, &PuzzleOptions::default(), 1);
+            assert!(puzzle.is_empty());
+            assert!(placed.is_empty());
+        }
+    }
+
+    #[test]
+    fn it_fills_a_fraction_of_the_empty_spots() {
+        let mut rng = StdRng::seed_from_u64(8);
+        let count_blanks = |puzzle: &[Vec<char>]| puzzle.iter().flatten().filter(|cell| **cell == ' ').count();
 
-        assert!(word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::Left, 1));
-        assert!(!word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::Left, 2));
+        let mut puzzle = generate_empty_puzzle(4, 5);
+        remove_empty_spots_partial(&mut puzzle, 0.0, &mut rng);
+        assert_eq!(count_blanks(&puzzle), 20);
 
-        assert!(!word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::UpLeft, 2));
-        assert!(!word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::Up, 2));
-        assert!(!word_fits(&puzzle, &Coordinate::new(0, 0), &Direction::UpRight, 2));
+        remove_empty_spots_partial(&mut puzzle, 0.25, &mut rng);
+        assert_eq!(count_blanks(&puzzle), 15);
+
+        let mut puzzle = generate_empty_puzzle(4, 5);
+        puzzle[0][0] = 'x';
+        remove_empty_spots_partial(&mut puzzle, 1.5, &mut rng);
+        assert_eq!(puzzle[0][0], 'x');
+        assert!(puzzle.iter().flatten().skip(1).all(|cell| cell.is_ascii_uppercase()));
+    }
+
+    #[test]
+    fn it_hides_phrases_without_spaces_or_hyphens() {
+        let words = vec!["new york", "T-Rex", "new york city"];
+        let (puzzle, placed, not_placed) = generate_puzzle_with_solution_seeded(8, 8, &words, 5);
+        assert_eq!(placed.iter().map(|placed_word| placed_word.word.as_str()).collect::<Vec<&str>>(), vec!["NEWYORK", "TREX"]);
+        assert_eq!(not_placed, vec!["new york city"]);
+        assert_eq!(find_words(&puzzle, &["new york", "t-rex"]), placed);
     }
 }
@@ -0,0 +1,64 @@
+use crate::generator::PlacedWord;
+use serde::{Deserialize, Serialize};
+
+/// A finished puzzle with everything needed to show it again, including the answers. Unlike
+/// `ShareParams` this doesn't depend on regenerating the grid, so it stays the same even if
+/// generation changes.
+#[derive(Debug, PartialEq, Clone, Serialize, Deserialize)]
+pub struct Puzzle {
+    pub width: i16,
+    pub height: i16,
+    pub grid: Vec<Vec<char>>,
+    pub placed_words: Vec<PlacedWord>,
+    /// Words that were asked for but could not be placed.
+    pub unplaced_words: Vec<String>,
+}
+
+impl Puzzle {
+    pub fn new(grid: Vec<Vec<char>>, placed_words: Vec<PlacedWord>, unplaced_words: Vec<String>) -> Puzzle {
+        let height = grid.len() as i16;
+        let width = grid.first().map(|row| row.len()).unwrap_or(0) as i16;
+        Puzzle { width, height, grid, placed_words, unplaced_words }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("A puzzle only holds types that serialize to JSON")
+    }
+
+    pub fn from_json(json: &str) -> Result<Puzzle, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+#[cfg(test)]
+mod json_tests {
+    use super::*;
+    use crate::generator::{generate_puzzle_with_options, Coordinate, Direction, PuzzleOptions};
+
+    #[test]
+    fn it_round_trips_a_generated_puzzle() {
+        let (grid, placed_words, failures) = generate_puzzle_with_options(6, 5, &["cat", "horse", "owl", "elephant"], &PuzzleOptions::default(), 3);
+        let unplaced_words = failures.iter().map(|failure| failure.word.clone()).collect();
+        let puzzle = Puzzle::new(grid, placed_words, unplaced_words);
+        assert_eq!((puzzle.width, puzzle.height), (6, 5));
+        assert_eq!(puzzle.unplaced_words, vec!["elephant".to_string()]);
+
+        assert_eq!(Puzzle::from_json(&puzzle.to_json()).unwrap(), puzzle);
+    }
+
+    #[test]
+    fn it_writes_placements_readably() {
+        let placed = PlacedWord { word: "OX".to_string(), start: Coordinate::new(1, 0), direction: Direction::UpRight };
+        let puzzle = Puzzle::new(vec![vec!['A', 'X'], vec!['O', 'B']], vec![placed], Vec::new());
+        assert_eq!(
+            puzzle.to_json(),
+            r#"{"width":2,"height":2,"grid":[["A","X"],["O","B"]],"placed_words":[{"word":"OX","start":{"row":1,"column":0},"direction":"UpRight"}],"unplaced_words":[]}"#,
+        );
+    }
+
+    #[test]
+    fn it_rejects_invalid_json() {
+        assert!(Puzzle::from_json("").is_err());
+        assert!(Puzzle::from_json(r#"{"width":2}"#).is_err());
+    }
+}
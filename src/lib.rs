@@ -1,5 +1,11 @@
 
+#[cfg(feature = "client")]
 pub mod client;
+pub mod export;
 pub mod generator;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod presets;
+pub mod share;
 
 
@@ -0,0 +1,41 @@
+
+/// A built-in word list that can be loaded into the form.
+#[derive(Debug, PartialEq)]
+pub struct Preset {
+    pub name: &'static str,
+    pub words: &'static [&'static str],
+}
+
+pub const ANIMALS: &[&str] = &["cat", "dog", "horse", "rabbit", "tiger", "zebra", "monkey", "giraffe"];
+pub const FRUITS: &[&str] = &["apple", "banana", "cherry", "grape", "lemon", "mango", "orange", "peach"];
+pub const COUNTRIES: &[&str] = &["brazil", "canada", "egypt", "france", "india", "japan", "kenya", "sweden"];
+
+pub const PRESETS: &[Preset] = &[
+    Preset { name: "Animals", words: ANIMALS },
+    Preset { name: "Fruits", words: FRUITS },
+    Preset { name: "Countries", words: COUNTRIES },
+];
+
+/// Looks up a preset by its name.
+pub fn find_preset(name: &str) -> Option<&'static Preset> {
+    PRESETS.iter().find(|preset| preset.name == name)
+}
+
+#[cfg(test)]
+mod preset_tests {
+    use super::*;
+
+    #[test]
+    fn it_has_short_ascii_word_lists() {
+        for preset in PRESETS {
+            assert!(!preset.words.is_empty());
+            assert!(preset.words.iter().all(|word| !word.is_empty() && word.chars().all(|letter| letter.is_ascii_alphabetic())));
+        }
+    }
+
+    #[test]
+    fn it_finds_presets_by_name() {
+        assert_eq!(find_preset("Fruits").map(|preset| preset.words), Some(FRUITS));
+        assert_eq!(find_preset("Vegetables"), None);
+    }
+}
@@ -0,0 +1,289 @@
+
+use crate::generator::Direction;
+
+/// Everything needed to regenerate a puzzle. Since generation is seeded, the same parameters
+/// always produce the same grid, so they can be shared as a link.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ShareParams {
+    pub seed: u64,
+    pub width: i16,
+    pub height: i16,
+    pub words: Vec<String>,
+    /// The directions words were allowed to run in, or empty for the default of every direction.
+    pub directions: Vec<Direction>,
+}
+
+impl ShareParams {
+    /// Encodes the parameters as a query string, without the leading `?`.
+    pub fn to_query_string(&self) -> String {
+        let words = self.words.iter()
+            .map(|word| percent_encode(word))
+            .collect::<Vec<String>>()
+            .join(",");
+        let query = format!("seed={}&width={}&height={}&words={}", self.seed, self.width, self.height, words);
+        if self.directions.is_empty() {
+            return query;
+        }
+        let directions = self.directions.iter()
+            .map(|direction| (*direction as u8).to_string())
+            .collect::<Vec<String>>()
+            .join(",");
+        format!("{}&directions={}", query, directions)
+    }
+
+    /// Parses a query string such as `location.search`, with or without the leading `?`. Accepts
+    /// either the separate parameters or a `code` from `encode_share_code`. Returns `None` if any
+    /// parameter is missing or invalid.
+    pub fn from_query_string(query: &str) -> Option<ShareParams> {
+        let code = query.trim_start_matches('?')
+            .split('&')
+            .find_map(|pair| pair.strip_prefix("code="));
+        if let Some(code) = code {
+            return decode_share_code(code);
+        }
+
+        let mut seed = None;
+        let mut width = None;
+        let mut height = None;
+        let mut words = None;
+        let mut directions = Some(Vec::new());
+        for pair in query.trim_start_matches('?').split('&') {
+            let Some((key, value)) = pair.split_once('=') else {
+                continue;
+            };
+            match key {
+                "seed" => seed = value.parse::<u64>().ok(),
+                "width" => width = value.parse::<i16>().ok(),
+                "height" => height = value.parse::<i16>().ok(),
+                "words" => words = value.split(',')
+                    .filter(|word| !word.is_empty())
+                    .map(percent_decode)
+                    .collect::<Option<Vec<String>>>(),
+                "directions" => directions = value.split(',')
+                    .map(|number| number.parse::<u8>().ok().and_then(Direction::from_number))
+                    .collect::<Option<Vec<Direction>>>(),
+                _ => {}
+            }
+        }
+        Some(ShareParams { seed: seed?, width: width?, height: height?, words: words?, directions: directions? })
+    }
+}
+
+/// The first byte of a share code, so the format can change without misreading old links.
+/// Version 2 added the directions byte after the dimensions.
+const SHARE_CODE_VERSION: u8 = 2;
+
+/// Packs the parameters into a short URL-safe string, much shorter than the query string or a
+/// JSON puzzle. The seed, dimensions and directions are stored as bytes followed by the words
+/// separated by newlines. The words themselves are kept rather than a hash of them, since the
+/// grid can't be regenerated without them.
+pub fn encode_share_code(params: &ShareParams) -> String {
+    let mut bytes = vec![SHARE_CODE_VERSION];
+    bytes.extend_from_slice(&params.seed.to_le_bytes());
+    bytes.extend_from_slice(&params.width.to_le_bytes());
+    bytes.extend_from_slice(&params.height.to_le_bytes());
+    bytes.push(params.directions.iter().fold(0, |mask, direction| mask | 1 << (*direction as u8 - 1)));
+    bytes.extend_from_slice(params.words.join("\n").as_bytes());
+    base64_encode(&bytes)
+}
+
+/// Reads a code made by `encode_share_code`, including codes from version 1, which had no
+/// directions. The directions come back in the order of `Direction::ALL`. Returns `None` if the
+/// code is damaged or from an unknown version.
+pub fn decode_share_code(code: &str) -> Option<ShareParams> {
+    let bytes = base64_decode(code)?;
+    let (&version, rest) = bytes.split_first()?;
+    let header = match version {
+        1 => 12,
+        SHARE_CODE_VERSION => 13,
+        _ => return None,
+    };
+    if rest.len() < header {
+        return None;
+    }
+    let seed = u64::from_le_bytes(rest[0..8].try_into().ok()?);
+    let width = i16::from_le_bytes(rest[8..10].try_into().ok()?);
+    let height = i16::from_le_bytes(rest[10..12].try_into().ok()?);
+    let mask = if version == 1 { 0 } else { rest[12] };
+    let directions = Direction::ALL.iter()
+        .filter(|direction| mask & 1 << (**direction as u8 - 1) != 0)
+        .copied()
+        .collect::<Vec<Direction>>();
+    let words = std::str::from_utf8(&rest[header..]).ok()?
+        .split('\n')
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_string())
+        .collect::<Vec<String>>();
+    if words.is_empty() {
+        return None;
+    }
+    Some(ShareParams { seed, width, height, words, directions })
+}
+
+/// The URL-safe base64 alphabet, so codes can go in a link without escaping.
+const BASE64_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encodes `bytes` as URL-safe base64 without padding.
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (index, byte)| group | (*byte as u32) << (16 - 8 * index));
+        for index in 0..=chunk.len() {
+            encoded.push(BASE64_ALPHABET[(group >> (18 - 6 * index) & 0x3F) as usize] as char);
+        }
+    }
+    encoded
+}
+
+fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let mut decoded = Vec::with_capacity(text.len() * 3 / 4);
+    for chunk in text.as_bytes().chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+        let mut group = 0u32;
+        for (index, character) in chunk.iter().enumerate() {
+            let value = BASE64_ALPHABET.iter().position(|letter| letter == character)? as u32;
+            group |= value << (18 - 6 * index);
+        }
+        for index in 0..chunk.len() - 1 {
+            decoded.push((group >> (16 - 8 * index)) as u8);
+        }
+    }
+    Some(decoded)
+}
+
+fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+fn percent_decode(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        match bytes[index] {
+            b'%' => {
+                let hex = text.get(index + 1..index + 3)?;
+                decoded.push(u8::from_str_radix(hex, 16).ok()?);
+                index += 3;
+            }
+            b'+' => {
+                decoded.push(b' ');
+                index += 1;
+            }
+            byte => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+#[cfg(test)]
+mod share_tests {
+    use super::*;
+
+    #[test]
+    fn it_round_trips_the_parameters() {
+        let params = ShareParams {
+            seed: 18_446_744_073_709_551_615,
+            width: 12,
+            height: 8,
+            words: vec!["cat".to_string(), "ice cream".to_string(), "a,b&c".to_string(), "café".to_string()],
+            directions: Vec::new(),
+        };
+        let query = params.to_query_string();
+        assert_eq!(query, "seed=18446744073709551615&width=12&height=8&words=cat,ice%20cream,a%2Cb%26c,caf%C3%A9");
+        assert_eq!(ShareParams::from_query_string(&query), Some(params.clone()));
+        assert_eq!(ShareParams::from_query_string(&format!("?{}", query)), Some(params));
+    }
+
+    #[test]
+    fn it_round_trips_the_directions_in_a_query_string() {
+        let params = ShareParams { directions: vec![Direction::Right, Direction::Down], ..params() };
+        let query = params.to_query_string();
+        assert!(query.ends_with("&directions=4,2"));
+        assert_eq!(ShareParams::from_query_string(&query), Some(params));
+        assert_eq!(ShareParams::from_query_string("?seed=1&width=5&height=5&words=cat&directions=9"), None);
+    }
+
+    fn params() -> ShareParams {
+        ShareParams {
+            seed: 9_876_543_210,
+            width: 12,
+            height: 10,
+            words: vec!["apple".to_string(), "banana".to_string(), "ice cream".to_string(), "café".to_string()],
+            directions: vec![Direction::Down, Direction::Right],
+        }
+    }
+
+    #[test]
+    fn it_round_trips_a_share_code() {
+        let code = encode_share_code(&params());
+        assert!(code.bytes().all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_'));
+        assert_eq!(decode_share_code(&code), Some(params()));
+        assert_eq!(ShareParams::from_query_string(&format!("?code={}", code)), Some(params()));
+
+        let every_direction = ShareParams { directions: Vec::new(), ..params() };
+        assert_eq!(decode_share_code(&encode_share_code(&every_direction)), Some(every_direction));
+    }
+
+    #[test]
+    fn it_reads_version_one_share_codes() {
+        let mut bytes = vec![1];
+        bytes.extend_from_slice(&params().seed.to_le_bytes());
+        bytes.extend_from_slice(&params().width.to_le_bytes());
+        bytes.extend_from_slice(&params().height.to_le_bytes());
+        bytes.extend_from_slice(params().words.join("\n").as_bytes());
+        assert_eq!(decode_share_code(&base64_encode(&bytes)), Some(ShareParams { directions: Vec::new(), ..params() }));
+    }
+
+    #[test]
+    fn it_round_trips_base64_of_every_length() {
+        let bytes = (0..=255).collect::<Vec<u8>>();
+        for length in 0..8 {
+            assert_eq!(base64_decode(&base64_encode(&bytes[..length])), Some(bytes[..length].to_vec()));
+        }
+        assert_eq!(base64_encode(b"Man"), "TWFu");
+        assert_eq!(base64_encode(b"Ma"), "TWE");
+    }
+
+    #[test]
+    fn it_rejects_damaged_share_codes() {
+        assert_eq!(decode_share_code(""), None);
+        assert_eq!(decode_share_code("not base64!"), None);
+        let code = encode_share_code(&params());
+        assert_eq!(decode_share_code(&code[..10]), None);
+        let mut wrong_version = base64_decode(&code).unwrap();
+        wrong_version[0] = 3;
+        assert_eq!(decode_share_code(&base64_encode(&wrong_version)), None);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn it_is_much_shorter_than_the_json() {
+        use crate::generator::{generate_puzzle_with_options, PuzzleOptions};
+        use crate::json::Puzzle;
+
+        let params = params();
+        let words = params.words.iter().map(|word| word.as_str()).collect::<Vec<&str>>();
+        let (grid, placed_words, _) = generate_puzzle_with_options(params.width, params.height, &words, &PuzzleOptions::default(), params.seed);
+        let json = Puzzle::new(grid, placed_words, Vec::new()).to_json();
+        assert!(encode_share_code(&params).len() * 5 < json.len());
+    }
+
+    #[test]
+    fn it_rejects_missing_or_invalid_parameters() {
+        assert_eq!(ShareParams::from_query_string(""), None);
+        assert_eq!(ShareParams::from_query_string("?seed=1&width=5&words=cat"), None);
+        assert_eq!(ShareParams::from_query_string("?seed=1&width=five&height=5&words=cat"), None);
+        assert_eq!(ShareParams::from_query_string("?seed=1&width=5&height=5&words=%ZZ"), None);
+    }
+}
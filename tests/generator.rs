@@ -0,0 +1,22 @@
+use word_search::export::puzzle_to_string;
+use word_search::generator::{find_words, generate_puzzle_with_options, Coordinate, Direction, PlacedWord, PuzzleOptions};
+
+#[test]
+fn it_generates_a_puzzle_without_the_client() {
+    let words = vec!["rust", "crate", "cargo"];
+    let (puzzle, placed, failures) = generate_puzzle_with_options(8, 8, &words, &PuzzleOptions::default(), 42);
+
+    assert!(failures.is_empty());
+    assert_eq!(puzzle.len(), 8);
+    assert!(puzzle.iter().all(|row| row.len() == 8 && row.iter().all(char::is_ascii_uppercase)));
+    assert_eq!(puzzle_to_string(&puzzle).lines().count(), 8);
+
+    let found = find_words(&puzzle, &words);
+    assert!(placed.iter().all(|placed_word| found.contains(placed_word)));
+}
+
+#[test]
+fn it_exposes_the_core_types() {
+    let placed = PlacedWord { word: "AB".to_string(), start: Coordinate::new(1, 1), direction: Direction::Down };
+    assert_eq!(placed.coordinates(), vec![Coordinate::new(1, 1), Coordinate::new(2, 1)]);
+}